@@ -31,7 +31,7 @@ impl Data {
         let leagues = read_data!("leagues.json", League);
         let events = read_data!("events.json", Event);
         let mut sessions = read_data!("sessions.json", Session);
-        let tracks = read_data!("tracks.json", Track);
+        let mut tracks = read_data!("tracks.json", Track);
         let league_entries = read_data!("league_entries.json", LeagueEntry);
 
         for session in sessions.iter_mut() {
@@ -40,6 +40,15 @@ impl Data {
             })
         }
 
+        // Every track in the game can be resolved, even when it isn't in the data file yet
+        for game_track in telemetry::packet::session::Track::ALL {
+            if let Some(game_track) = Track::from_game(game_track) {
+                if !tracks.iter().any(|track| track.id == game_track.id) {
+                    tracks.push(game_track);
+                }
+            }
+        }
+
         Self {
             drivers: users,
             leagues,
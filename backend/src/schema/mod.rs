@@ -542,6 +542,17 @@ impl Overtake {
 pub struct Team {
     id: ID,
     name: String,
+    nationality: String, // Alpha 2 Code
+}
+
+impl telemetry::metadata::team::LeagueTeam for Team {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn nationality(&self) -> &str {
+        &self.nationality
+    }
 }
 
 #[derive(SimpleObject, Clone, Deserialize)]
@@ -551,6 +562,19 @@ pub struct Track {
     country: String, // Alpha 2 Code
 }
 
+impl Track {
+    /// Builds the track from the game's metadata, the id matches the track's id in `tracks.json`
+    pub fn from_game(track: telemetry::packet::session::Track) -> Option<Self> {
+        let info = track.info()?;
+
+        Some(Self {
+            id: ID(info.id.to_owned()),
+            name: info.name.to_owned(),
+            country: info.country.alpha2.to_owned(),
+        })
+    }
+}
+
 // TODO Login Details, Account stuff
 #[derive(SimpleObject, Clone, Deserialize)]
 pub struct Driver {
@@ -2,8 +2,11 @@ use clap::{Parser, Subcommand, ValueEnum};
 use color_eyre::eyre::Result;
 use tracing::level_filters::LevelFilter;
 
-use std::path::PathBuf;
-use telemetry_cli::{initialize, parse, race2, record};
+use std::{net::IpAddr, path::PathBuf, time::Duration};
+use telemetry_cli::{
+    bookmarks, compress, index, initialize, merge, parse, race2, record, repair, replay, weekend,
+    RecordOptions, ReplaySpeed,
+};
 
 #[derive(Parser, Debug)]
 #[command(author = "Warre Dujardin", version = "0.1.0", about = "Utility for recording and parsing F1 telemetry packets", long_about = None)]
@@ -28,6 +31,8 @@ enum PacketId {
     LobbyInfo,
     CarDamage,
     SessionHistory,
+    TyreSets,
+    MotionEx,
 }
 
 impl From<PacketId> for telemetry::packet::header::PacketId {
@@ -47,6 +52,8 @@ impl From<PacketId> for telemetry::packet::header::PacketId {
             PacketId::LobbyInfo => telemetry::packet::header::PacketId::LobbyInfo,
             PacketId::CarDamage => telemetry::packet::header::PacketId::CarDamage,
             PacketId::SessionHistory => telemetry::packet::header::PacketId::SessionHistory,
+            PacketId::TyreSets => telemetry::packet::header::PacketId::TyreSets,
+            PacketId::MotionEx => telemetry::packet::header::PacketId::MotionEx,
         }
     }
 }
@@ -57,6 +64,36 @@ enum Commands {
     Record {
         #[clap(short, long)]
         file: PathBuf,
+        #[clap(long, help = "TOML file with defaults for the options below")]
+        config: Option<PathBuf>,
+        #[clap(
+            short,
+            long,
+            help = "Address of the interface to listen on [default: 0.0.0.0]"
+        )]
+        bind: Option<IpAddr>,
+        #[clap(
+            short,
+            long,
+            value_delimiter = ',',
+            help = "Ports to listen on, frames are tagged with the port [default: 20777]"
+        )]
+        port: Vec<u16>,
+        #[clap(long, help = "Size of the socket receive buffers in bytes")]
+        receive_buffer_size: Option<usize>,
+        #[clap(short, long, help = "Compresses the recording with zstd")]
+        compress: bool,
+        #[clap(
+            long,
+            value_delimiter = ',',
+            help = "Addresses to send every received packet on to, e.g. for SimHub"
+        )]
+        forward: Vec<String>,
+        #[clap(
+            long,
+            help = "Writes every session to its own file, named after the file template, e.g. {date}_{track}_{session_type}.bin"
+        )]
+        split: bool,
     },
     #[clap(about = "Parse a previously recorded stream of UDP packets")]
     Parse {
@@ -80,6 +117,85 @@ enum Commands {
         #[clap(short, long)]
         out: Option<PathBuf>,
     },
+    #[clap(
+        about = "Group the sessions of one or more recordings into a race weekend for the website"
+    )]
+    Weekend {
+        #[clap(short, long, num_args = 1.., required = true)]
+        files: Vec<PathBuf>,
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+        #[clap(long)]
+        weekend_id: Option<u32>,
+    },
+    #[clap(about = "Compress a previously recorded stream of UDP packets with zstd")]
+    Compress {
+        #[clap(short, long)]
+        file: PathBuf,
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+    },
+    #[clap(
+        about = "Copy the intact frames of a damaged recording to a new one and report what was lost"
+    )]
+    Repair {
+        #[clap(short, long)]
+        file: PathBuf,
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+    },
+    #[clap(about = "Merge the recordings several players made of the same sessions into one")]
+    Merge {
+        #[clap(short, long, num_args = 1.., required = true)]
+        files: Vec<PathBuf>,
+        #[clap(short, long)]
+        out: PathBuf,
+        #[clap(long)]
+        session_id: Option<u64>,
+        #[clap(short, long, help = "Compresses the merged recording with zstd")]
+        compress: bool,
+    },
+    #[clap(
+        about = "Send a previously recorded stream of UDP packets to an address, like the game"
+    )]
+    Replay {
+        #[clap(short, long)]
+        file: PathBuf,
+        #[clap(short, long, default_value = "127.0.0.1:20777")]
+        target: String,
+        #[clap(
+            short,
+            long,
+            default_value = "1",
+            help = "Multiplier like 0.5 or 2x, or max to send as fast as possible"
+        )]
+        speed: ReplaySpeed,
+        #[clap(long, value_parser = parse_seconds, help = "Seconds from the start of the recording")]
+        start: Option<Duration>,
+        #[clap(long, value_parser = parse_seconds, help = "Seconds from the start of the recording")]
+        end: Option<Duration>,
+        #[clap(long)]
+        session_id: Option<u64>,
+    },
+    #[clap(about = "Index a previously recorded stream of UDP packets so it can be parsed faster")]
+    Index {
+        #[clap(short, long)]
+        file: PathBuf,
+    },
+    #[clap(about = "List the moments drivers marked with the UDP action buttons")]
+    Bookmarks {
+        #[clap(short, long)]
+        file: PathBuf,
+        #[clap(short, long)]
+        out: Option<PathBuf>,
+    },
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("expected a number of seconds, got {s}"))
 }
 
 fn main() -> Result<()> {
@@ -94,8 +210,36 @@ fn main() -> Result<()> {
     initialize(log_level)?;
 
     match cli.command {
-        Commands::Record { file } => {
-            record(file, "0.0.0.0:20777")?;
+        Commands::Record {
+            file,
+            config,
+            bind,
+            port,
+            receive_buffer_size,
+            compress,
+            forward,
+            split,
+        } => {
+            let mut options = match config {
+                Some(config) => RecordOptions::load(config)?,
+                None => RecordOptions::default(),
+            };
+            if let Some(bind) = bind {
+                options.bind = bind;
+            }
+            if !port.is_empty() {
+                options.ports = port;
+            }
+            if receive_buffer_size.is_some() {
+                options.receive_buffer_size = receive_buffer_size;
+            }
+            options.compress |= compress;
+            options.split |= split;
+            if !forward.is_empty() {
+                options.forward = forward;
+            }
+
+            record(file, &options)?;
         }
         Commands::Parse {
             file,
@@ -115,6 +259,43 @@ fn main() -> Result<()> {
         Commands::Race { file, out } => {
             race2(file, out)?;
         }
+        Commands::Weekend {
+            files,
+            out,
+            weekend_id,
+        } => {
+            weekend(&files, out, weekend_id)?;
+        }
+        Commands::Compress { file, out } => {
+            compress(file, out)?;
+        }
+        Commands::Repair { file, out } => {
+            repair(file, out)?;
+        }
+        Commands::Merge {
+            files,
+            out,
+            session_id,
+            compress,
+        } => {
+            merge(&files, out, session_id, compress)?;
+        }
+        Commands::Replay {
+            file,
+            target,
+            speed,
+            start,
+            end,
+            session_id,
+        } => {
+            replay(file, &target, speed, start, end, session_id)?;
+        }
+        Commands::Index { file } => {
+            index(file)?;
+        }
+        Commands::Bookmarks { file, out } => {
+            bookmarks(file, out)?;
+        }
     }
 
     Ok(())
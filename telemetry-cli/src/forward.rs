@@ -0,0 +1,67 @@
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use tracing::{debug, warn};
+
+use crate::sending_socket;
+
+/// Sends every datagram that is received on to other addresses, so other telemetry tools can
+/// still be used while recording.
+///
+/// Failing to forward never stops the recording, it is only reported when a target starts and
+/// stops failing so a target that is down doesn't flood the log.
+pub struct Forwarder {
+    targets: Vec<Target>,
+}
+
+struct Target {
+    address: SocketAddr,
+    /// Bound to the address family of the target, and separate from the socket that is listened
+    /// on so errors of a target can't end up there
+    socket: UdpSocket,
+    failing: bool,
+}
+
+impl Forwarder {
+    /// Resolves the targets once, so no lookups happen while forwarding
+    pub fn new<A: AsRef<str>>(targets: &[A]) -> eyre::Result<Self> {
+        let targets = targets
+            .iter()
+            .map(|target| {
+                let target = target.as_ref();
+                let address = target
+                    .to_socket_addrs()?
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("Could not resolve forward address {target}"))?;
+                debug!(target, %address, "Forwarding packets");
+                Ok(Target {
+                    address,
+                    socket: sending_socket(address)?,
+                    failing: false,
+                })
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self { targets })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    pub fn forward(&mut self, datagram: &[u8]) {
+        for target in &mut self.targets {
+            match target.socket.send_to(datagram, target.address) {
+                Ok(_) if target.failing => {
+                    warn!("Forwarding to {} works again", target.address);
+                    target.failing = false;
+                }
+                Ok(_) => {}
+                Err(e) if !target.failing => {
+                    warn!("Could not forward packet to {}: {}", target.address, e);
+                    target.failing = true;
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
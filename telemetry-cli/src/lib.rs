@@ -1,31 +1,48 @@
 use std::{
     collections::HashMap,
-    io::{Read, Write},
-    net::UdpSocket,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
     path::Path,
+    sync::mpsc::{self, RecvTimeoutError},
     time::{Duration, Instant},
-    vec,
 };
 
-use bytes::{Buf, Bytes};
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
 use telemetry::{
     decode_header, decode_packet,
+    listener::{Listener, RecvError},
     packet::{
-        event::{Event, Penalty},
+        car_setups::CarSetupData,
+        event::{ButtonTracker, Event, Penalty},
         final_classification::TyreStint,
         header::PacketId,
-        lap_data::{PitStatus, ResultStatus},
+        lap_data::ResultStatus,
         participants::Team,
-        session::{SafetyCarStatus, SessionLength, SessionType, Track},
-        session_history::{LapHistoryData, SessionHistoryPacket},
+        session::{SessionType, Track},
         Packet,
     },
+    session_state::SessionState,
 };
 use tracing::{debug, level_filters::LevelFilter, warn};
 use tracing_subscriber::FmtSubscriber;
 
+use self::forward::Forwarder;
+use self::merge::RecordingMerger;
+use self::recording::{
+    index::RecordingIndex, repair::RecordingScanner, Frame, RecordingHeader, RecordingReader,
+    RecordingWriter, FLUSH_INTERVAL, VERSION,
+};
+use self::split::SessionSplitter;
+
+pub mod forward;
+pub mod merge;
+pub mod recording;
+pub mod split;
+#[cfg(test)]
+mod test_util;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct ParsedSessions {
     sessions: Vec<ParsedSessionData>,
@@ -35,11 +52,33 @@ struct ParsedSessions {
 struct ParsedSessionData {
     session_id: u64,
     session_link_id: u32,
+    weekend_link_id: u32,
+    season_link_id: u32,
     session_type: SessionType,
     track: Track,
+    start_time: Option<DateTime<Utc>>, // Only known for recordings with a header
     participants: Vec<SessionParticipant>,
 }
 
+// All sessions of a single race weekend, to be imported as one event
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ParsedWeekend {
+    season_link_id: u32,
+    weekend_link_id: u32,
+    name: String,
+    date: Option<DateTime<Utc>>, // Start of the last session, usually the race
+    track: Track,
+    track_id: Option<String>,
+    entries: Vec<WeekendEntry>,
+    sessions: Vec<ParsedSessionData>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct WeekendEntry {
+    driver: String,
+    team: Team,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 enum SessionParticipantStatus {
     Finished,
@@ -67,8 +106,7 @@ struct SessionParticipant {
     total_time_without_penalties: u64,
     penalty_time_in_s: u8,
     laps: Vec<LapData>,
-    #[serde(skip)]
-    session_history: Vec<LapHistoryData>,
+    setup: Option<CarSetupData>, // Last setup seen for this car
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -94,6 +132,18 @@ struct PlayerData {
     nationality: Option<celes::Country>,
 }
 
+// A UDP action button press, used by drivers to mark a moment during a session
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Bookmark {
+    time: Duration,
+    session_id: u64,
+    session_time: f32,
+    session_type: SessionType,
+    track: Track,
+    action: u8,
+    lap_number: Option<u8>,
+}
+
 // A packet that can be/was written to disk
 #[derive(Debug, Serialize, Deserialize)]
 struct DiskPacket {
@@ -109,19 +159,83 @@ pub fn initialize(level: impl Into<LevelFilter>) -> eyre::Result<()> {
     Ok(())
 }
 
-pub fn record<P: AsRef<Path>>(file_path: P, address: &str) -> eyre::Result<()> {
-    // open file
-    let socket = UdpSocket::bind(address)?;
-    debug!(address, "Opened socket");
+/// How `record` listens, can also be read from a TOML config file with the same keys
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RecordOptions {
+    /// Address of the interface to listen on, `::` for every IPv6 (and usually IPv4) interface
+    pub bind: IpAddr,
+    /// Every port gets its own socket, frames are tagged with the port they arrived on
+    pub ports: Vec<u16>,
+    /// Size of the receive buffer of every socket in bytes, the OS default if not set
+    pub receive_buffer_size: Option<usize>,
+    pub compress: bool,
+    /// Addresses every received packet is sent on to
+    pub forward: Vec<String>,
+    /// Writes every session to its own recording, the recording path is used as the template
+    /// for their names (see [`SessionSplitter`])
+    pub split: bool,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            bind: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            ports: vec![20777],
+            receive_buffer_size: None,
+            compress: false,
+            forward: Vec::new(),
+            split: false,
+        }
+    }
+}
+
+impl RecordOptions {
+    pub fn load<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
+        Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
 
-    let mut file = std::fs::File::create(file_path.as_ref())?;
+/// Records everything sent to the ports in `options`, and sends it on to every forward address
+pub fn record<P: AsRef<Path>>(file_path: P, options: &RecordOptions) -> eyre::Result<()> {
+    if options.ports.is_empty() {
+        eyre::bail!("No ports to listen on");
+    }
+
+    let addresses: Vec<_> = options
+        .ports
+        .iter()
+        .map(|&port| SocketAddr::new(options.bind, port))
+        .collect();
+    let sockets = addresses
+        .iter()
+        .map(|&address| bind_socket(address, options.receive_buffer_size))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    let address = addresses.iter().join(", ");
+    let mut forwarder = Forwarder::new(&options.forward)?;
+
+    // open file
+    let header = RecordingHeader::new(&address);
+    let mut file = if options.split {
+        RecordOutput::Split(SessionSplitter::new(
+            &file_path.as_ref().to_string_lossy(),
+            header,
+            options.compress,
+        ))
+    } else {
+        RecordOutput::Single(RecordingWriter::create_indexed(
+            file_path.as_ref(),
+            Some(&header),
+            options.compress,
+        )?)
+    };
     debug!(
         path = file_path.as_ref().to_string_lossy().as_ref(),
+        compress = options.compress,
+        split = options.split,
         "Opened file"
     );
 
-    let mut buf = [0; 2048]; // needs to be at least 1464, but we'll go for a nice, round, power of 2 instead
-
     let start_time = Instant::now();
 
     println!(
@@ -129,15 +243,78 @@ pub fn record<P: AsRef<Path>>(file_path: P, address: &str) -> eyre::Result<()> {
         address,
         file_path.as_ref().to_string_lossy()
     );
+    if !forwarder.is_empty() {
+        println!("Forwarding to {}", options.forward.join(", "));
+    }
+
+    // Every socket gets its own thread, frames are written here in the order they arrive
+    let (sender, receiver) = mpsc::channel();
+    for (socket, address) in sockets.into_iter().zip(addresses) {
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_io()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    warn!("Stopped listening on {}: {}", address, e);
+                    return;
+                }
+            };
+
+            runtime.block_on(async move {
+                let listener = match Listener::from_std(socket) {
+                    Ok(listener) => listener,
+                    Err(e) => {
+                        warn!("Stopped listening on {}: {}", address, e);
+                        return;
+                    }
+                };
+
+                loop {
+                    // Datagrams that can't be decoded are still recorded
+                    let (bytes, packet) = match listener.recv().await {
+                        Ok(received) => (received.bytes, Ok(received.packet)),
+                        Err(RecvError::Invalid { bytes, error, .. }) => (bytes, Err(error)),
+                        Err(RecvError::Io(e)) => {
+                            warn!("Stopped listening on {}: {}", address, e);
+                            break;
+                        }
+                    };
+                    let frame = Frame {
+                        time: start_time.elapsed(),
+                        port: Some(address.port()),
+                        packet: bytes,
+                    };
+                    if sender.send((frame, packet)).is_err() {
+                        // Stopped recording
+                        break;
+                    }
+                }
+            });
+        });
+    }
+    drop(sender);
+
+    loop {
+        let (frame, packet) = match receiver.recv_timeout(FLUSH_INTERVAL) {
+            Ok(received) => received,
+            // Nothing was received for a while, make sure the recording so far is on disk
+            Err(RecvTimeoutError::Timeout) => {
+                file.flush()?;
+                continue;
+            }
+            // Every socket stopped listening
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        forwarder.forward(&frame.packet);
 
-    while let Ok((size, _)) = socket.recv_from(&mut buf) {
-        let b = Bytes::copy_from_slice(&buf[..size]);
-        // let b = Bytes::copy_from_slice(&buf);
-        let packet = decode_packet(b.clone());
         match packet {
             Ok(packet) => {
                 debug!(
-                    size,
+                    size = frame.packet.len(),
+                    port = frame.port,
                     packet_id = packet.header().packet_id.to_string(),
                     "Received packet"
                 );
@@ -149,15 +326,388 @@ pub fn record<P: AsRef<Path>>(file_path: P, address: &str) -> eyre::Result<()> {
                 );
             }
         }
-        file.write_all(&(size as u64).to_be_bytes())?;
-        file.write_all(&(Instant::now() - start_time).as_secs_f64().to_be_bytes())?;
-        file.write_all(&b)?;
-        file.flush()?;
+        file.write_frame(frame)?;
+    }
+
+    file.finish()?;
+
+    Ok(())
+}
+
+/// Where `record` writes to
+enum RecordOutput {
+    Single(RecordingWriter),
+    Split(SessionSplitter),
+}
+
+impl RecordOutput {
+    fn write_frame(&mut self, frame: Frame) -> eyre::Result<()> {
+        match self {
+            RecordOutput::Single(writer) => {
+                writer.write_frame(frame.time, frame.port, &frame.packet)
+            }
+            RecordOutput::Split(splitter) => splitter.write_frame(frame),
+        }
+    }
+
+    fn flush(&mut self) -> eyre::Result<()> {
+        match self {
+            RecordOutput::Single(writer) => writer.flush(),
+            RecordOutput::Split(splitter) => splitter.flush(),
+        }
+    }
+
+    fn finish(self) -> eyre::Result<()> {
+        match self {
+            RecordOutput::Single(writer) => writer.finish(),
+            RecordOutput::Split(splitter) => splitter.finish(),
+        }
+    }
+}
+
+fn bind_socket(address: SocketAddr, receive_buffer_size: Option<usize>) -> eyre::Result<UdpSocket> {
+    let socket = Socket::new(
+        Domain::for_address(address),
+        Type::DGRAM,
+        Some(Protocol::UDP),
+    )?;
+    if let Some(size) = receive_buffer_size {
+        socket.set_recv_buffer_size(size)?;
+    }
+    socket.bind(&address.into())?;
+    debug!(
+        %address,
+        receive_buffer_size = socket.recv_buffer_size()?,
+        "Opened socket"
+    );
+    Ok(socket.into())
+}
+
+/// Compresses a recording, `out` defaults to the recording with `.zst` appended
+pub fn compress<P: AsRef<Path>, P2: AsRef<Path>>(
+    file: P,
+    out: Option<P2>,
+) -> Result<(), eyre::Error> {
+    let out = match out {
+        Some(out) => out.as_ref().to_owned(),
+        None => {
+            let mut out = file.as_ref().as_os_str().to_owned();
+            out.push(".zst");
+            out.into()
+        }
+    };
+
+    let recording = RecordingReader::open(&file)?;
+    debug!(header = ?recording.header(), "Opened recording");
+    let mut writer = RecordingWriter::create(&out, recording.header(), true)?;
+
+    for frame in recording {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("Could not read frame: {}", e);
+                break;
+            }
+        };
+        writer.write_frame(frame.time, frame.port, &frame.packet)?;
+    }
+    writer.finish()?;
+
+    println!(
+        "Compressed {:?} ({} bytes) to {:?} ({} bytes)",
+        file.as_ref(),
+        std::fs::metadata(&file)?.len(),
+        out,
+        std::fs::metadata(&out)?.len()
+    );
+
+    Ok(())
+}
+
+/// Writes the frames that are still intact in a damaged recording to a new one and reports
+/// what was lost. `out` defaults to the recording with `.repaired` before its extension.
+///
+/// The new recording gets checksums, unless it's a legacy recording without a header.
+pub fn repair<P: AsRef<Path>, P2: AsRef<Path>>(
+    file: P,
+    out: Option<P2>,
+) -> Result<(), eyre::Error> {
+    let file = file.as_ref();
+    let out = match out {
+        Some(out) => out.as_ref().to_owned(),
+        None => {
+            let mut name = file.file_stem().unwrap_or_default().to_owned();
+            name.push(".repaired");
+            if let Some(extension) = file.extension() {
+                name.push(".");
+                name.push(extension);
+            }
+            file.with_file_name(name)
+        }
+    };
+    if out == file {
+        eyre::bail!("Can't write the repaired recording over the damaged one");
+    }
+
+    let mut scanner = RecordingScanner::open(file)?;
+    debug!(header = ?scanner.header(), "Opened recording");
+    let header = scanner.header().map(|header| RecordingHeader {
+        version: VERSION,
+        ..header.clone()
+    });
+    let mut writer = RecordingWriter::create(&out, header.as_ref(), scanner.is_compressed())?;
+
+    let format_time = |time: Option<Duration>| match time {
+        Some(time) => format!("{:.3}s", time.as_secs_f64()),
+        None => "-".to_owned(),
+    };
+    let mut frames = 0;
+    let mut damage = Vec::new();
+    for frame in scanner.by_ref() {
+        match frame {
+            Ok(frame) => {
+                writer.write_frame(frame.time, frame.port, &frame.packet)?;
+                frames += 1;
+            }
+            Err(e) => damage.push(e),
+        }
+    }
+    writer.finish()?;
+    let length = scanner.offset();
+    let stream_error = scanner.stream_error();
+
+    println!("Kept {frames} frames, written to {:?}", out);
+    if let Some(e) = stream_error {
+        println!("Could only read the first {length} bytes: {e}");
+    }
+    if damage.is_empty() {
+        if stream_error.is_none() {
+            println!("No damaged frames found");
+        }
+        return Ok(());
+    }
+
+    println!(
+        "Dropped {} damaged part(s), {} of {length} bytes:",
+        damage.len(),
+        damage
+            .iter()
+            .map(|damage| damage.end - damage.start)
+            .sum::<u64>()
+    );
+    for damage in &damage {
+        println!(
+            "  {}..{} ({} bytes, between {} and {}): {}",
+            damage.start,
+            damage.end,
+            damage.end - damage.start,
+            format_time(damage.previous),
+            format_time(damage.next),
+            damage.reason
+        );
     }
 
     Ok(())
 }
 
+/// Merges recordings of the same sessions made by different players into one, see
+/// [`RecordingMerger`]. Only merges the session with `session_id` if one is given.
+pub fn merge<P: AsRef<Path>, P2: AsRef<Path>>(
+    files: &[P],
+    out: P2,
+    session_id: Option<u64>,
+    compress: bool,
+) -> Result<(), eyre::Error> {
+    let mut merger = RecordingMerger::new(session_id);
+    for file in files {
+        merger.add(file)?;
+    }
+    let sessions = merger.write(&out, compress)?;
+
+    if sessions.iter().all(|session| session.recordings < 2) {
+        warn!("The recordings have no session in common, nothing was merged");
+    }
+
+    println!("Merged {} recordings into {:?}", files.len(), out.as_ref());
+    for session in &sessions {
+        println!(
+            "Session {}: {} packets from {} recordings, {} duplicates dropped, {} completed with \
+             restricted telemetry",
+            session.session_uid,
+            session.packets,
+            session.recordings,
+            session.duplicates,
+            session.completed
+        );
+        for (file, only_in) in files.iter().zip(&session.only_in) {
+            println!("  {} packets only in {:?}", only_in, file.as_ref());
+        }
+    }
+
+    Ok(())
+}
+
+/// Socket to send datagrams to `address` from, bound to any port of the same address family
+pub(crate) fn sending_socket(address: SocketAddr) -> std::io::Result<UdpSocket> {
+    let unspecified: IpAddr = match address {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+    UdpSocket::bind((unspecified, 0))
+}
+
+/// How fast a recording is replayed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReplaySpeed {
+    /// 2.0 replays twice as fast as it was recorded
+    Multiplier(f64),
+    /// Send every frame as soon as the previous one was sent
+    Max,
+}
+
+impl std::str::FromStr for ReplaySpeed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "max" {
+            return Ok(Self::Max);
+        }
+        match s.trim_end_matches('x').parse::<f64>() {
+            Ok(multiplier) if multiplier > 0.0 && multiplier.is_finite() => {
+                Ok(Self::Multiplier(multiplier))
+            }
+            _ => Err(format!(
+                "expected a positive multiplier (e.g. 0.5 or 2x) or max, got {s}"
+            )),
+        }
+    }
+}
+
+/// Sends the frames of a recording to `target` with the timing they were recorded with.
+///
+/// `start` and `end` are offsets from the start of the recording.
+pub fn replay<P: AsRef<Path>>(
+    file: P,
+    target: &str,
+    speed: ReplaySpeed,
+    start: Option<Duration>,
+    end: Option<Duration>,
+    session_id: Option<u64>,
+) -> eyre::Result<()> {
+    let recording = open_indexed(&file, session_id, None)?;
+    let address = target
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| eyre::eyre!("Could not resolve replay address {target}"))?;
+    let socket = sending_socket(address)?;
+    debug!(target, %address, ?speed, "Opened socket");
+
+    println!(
+        "Replaying {}\nSending to {}",
+        file.as_ref().to_string_lossy(),
+        target
+    );
+
+    // Recording time of the first frame that was sent, and when it was sent
+    let mut replay_start: Option<(Duration, Instant)> = None;
+    let mut sent = 0;
+
+    for frame in recording {
+        let Frame {
+            time,
+            packet: packet_bytes,
+            ..
+        } = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("Could not read frame: {}", e);
+                break;
+            }
+        };
+
+        if start.is_some_and(|start| time < start) {
+            continue;
+        }
+        if end.is_some_and(|end| time > end) {
+            break;
+        }
+        if let Some(session_id) = session_id {
+            match decode_header(packet_bytes.clone()) {
+                Ok(header) if header.session_uid == session_id => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!("Could not parse header: {}", e);
+                    continue;
+                }
+            }
+        }
+
+        let (first_time, started_at) = *replay_start.get_or_insert((time, Instant::now()));
+        if let ReplaySpeed::Multiplier(multiplier) = speed {
+            let due = started_at + time.saturating_sub(first_time).div_f64(multiplier);
+            std::thread::sleep(due.saturating_duration_since(Instant::now()));
+        }
+
+        match socket.send_to(&packet_bytes, address) {
+            Ok(_) => {
+                sent += 1;
+                debug!(size = packet_bytes.len(), ?time, "Sent packet");
+            }
+            Err(e) => {
+                warn!("Could not send packet: {}", e);
+            }
+        }
+    }
+
+    println!(
+        "Sent {} packets in {:.1}s",
+        sent,
+        replay_start.map_or(0.0, |(_, started_at)| started_at.elapsed().as_secs_f64())
+    );
+
+    Ok(())
+}
+
+/// Indexes a recording so it can be parsed without reading all of it, see [`RecordingIndex`]
+pub fn index<P: AsRef<Path>>(file: P) -> Result<(), eyre::Error> {
+    let index = RecordingIndex::build(&file)?;
+    index.save(&file)?;
+
+    println!(
+        "Indexed {} sessions, wrote index to {:?}",
+        index.sessions.len(),
+        RecordingIndex::path(&file)
+    );
+
+    Ok(())
+}
+
+/// Opens a recording, skipping straight to the frames of the session and packet types if the
+/// recording was indexed. Frames written after the index was last saved are still read.
+fn open_indexed<P: AsRef<Path>>(
+    file: P,
+    session_id: Option<u64>,
+    filter: Option<&[PacketId]>,
+) -> Result<RecordingReader, eyre::Error> {
+    let recording = RecordingReader::open(&file)?;
+    debug!(header = ?recording.header(), "Opened recording");
+    if session_id.is_none() && filter.is_none() {
+        return Ok(recording);
+    }
+
+    match RecordingIndex::load(&file, recording.header()) {
+        Ok(Some(index)) => {
+            debug!("Using recording index");
+            Ok(recording.with_ranges(index.ranges(session_id, filter)))
+        }
+        Ok(None) => Ok(recording),
+        Err(e) => {
+            warn!("Could not load recording index: {}", e);
+            Ok(recording)
+        }
+    }
+}
+
 pub fn parse<P: AsRef<Path>>(
     file: P,
     out: Option<P>,
@@ -165,17 +715,20 @@ pub fn parse<P: AsRef<Path>>(
     limit: Option<usize>,
     session_id: Option<u64>,
 ) -> Result<(), eyre::Error> {
-    let mut file = std::fs::File::open(file)?;
-    let metadata = file.metadata()?;
-    let mut buf = vec![0_u8; metadata.len() as usize];
+    let recording = open_indexed(&file, session_id, filter.as_deref())?;
     let mut packets: Vec<DiskPacket> = Vec::new();
-    file.read_exact(&mut buf)?;
-    let mut bytes = Bytes::copy_from_slice(&buf);
-    while bytes.has_remaining() {
-        let size = bytes.get_u64() as usize;
-        let time = bytes.get_f64();
-        let time = Duration::from_secs_f64(time);
-        let packet_bytes = bytes.copy_to_bytes(size);
+    for frame in recording {
+        let Frame {
+            time,
+            packet: packet_bytes,
+            ..
+        } = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("Could not read frame: {}", e);
+                break;
+            }
+        };
 
         if let Some(true) = limit.and_then(|l| Some(packets.len() > l)) {
             break;
@@ -196,12 +749,6 @@ pub fn parse<P: AsRef<Path>>(
                 }
                 match decode_packet(packet_bytes) {
                     Ok(packet) => {
-                        if let Packet::Event(event_packet) = packet {
-                            if let Event::Button { .. } = event_packet.event {
-                                continue;
-                            }
-                        };
-
                         packets.push(DiskPacket { time, packet });
                     }
                     Err(e) => {
@@ -226,24 +773,6 @@ pub fn parse<P: AsRef<Path>>(
     Ok(())
 }
 
-struct SessionState {
-    packets: Vec<DiskPacket>,
-}
-
-impl SessionState {
-    fn new() -> Self {
-        Self {
-            packets: Vec::new(),
-        }
-    }
-}
-
-impl Default for SessionState {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 // pub fn race<P: AsRef<Path>>(file: P, out: Option<P>) -> Result<(), eyre::Error> {
 //     let mut file = std::fs::File::open(file)?;
 //     let metadata = file.metadata()?;
@@ -466,20 +995,26 @@ impl Default for SessionState {
 //     Ok(())
 // }
 
-pub fn race2<P: AsRef<Path>, P2: AsRef<Path>>(file: P, out: Option<P2>) -> Result<(), eyre::Error> {
-    let mut file = std::fs::File::open(file)?;
-    let metadata = file.metadata()?;
-    let mut buf = vec![0_u8; metadata.len() as usize];
-    file.read_exact(&mut buf)?;
-    let mut bytes = Bytes::copy_from_slice(&buf);
-
-    let mut sessions: HashMap<u64, SessionState> = HashMap::new();
-
-    while bytes.has_remaining() {
-        let size = bytes.get_u64() as usize;
-        let time = bytes.get_f64();
-        let time = Duration::from_secs_f64(time);
-        let packet_bytes = bytes.copy_to_bytes(size);
+/// Parses every session in a recording, in the order they started
+fn read_sessions<P: AsRef<Path>>(file: P) -> Result<Vec<ParsedSessionData>, eyre::Error> {
+    let recording = RecordingReader::open(file)?;
+    let header = recording.header().cloned();
+    debug!(?header, "Opened recording");
+
+    let mut sessions: HashMap<u64, (Duration, SessionState)> = HashMap::new();
+
+    for frame in recording {
+        let Frame {
+            time,
+            packet: packet_bytes,
+            ..
+        } = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("Could not read frame: {}", e);
+                break;
+            }
+        };
         match decode_header(packet_bytes.clone()) {
             Ok(header) => {
                 if ![
@@ -489,6 +1024,7 @@ pub fn race2<P: AsRef<Path>, P2: AsRef<Path>>(file: P, out: Option<P2>) -> Resul
                     PacketId::Session,
                     PacketId::SessionHistory,
                     PacketId::Event,
+                    PacketId::CarSetups,
                 ]
                 .contains(&header.packet_id)
                     || header.session_uid == 0
@@ -497,16 +1033,11 @@ pub fn race2<P: AsRef<Path>, P2: AsRef<Path>>(file: P, out: Option<P2>) -> Resul
                 }
                 match decode_packet(packet_bytes.clone()) {
                     Ok(packet) => {
-                        if let Packet::Event(event_packet) = packet {
-                            if let Event::Button { .. } = event_packet.event {
-                                continue;
-                            }
-                        };
-
-                        let session = sessions
+                        sessions
                             .entry(packet.header().session_uid)
-                            .or_insert(SessionState::default());
-                        session.packets.push(DiskPacket { time, packet });
+                            .or_insert_with(|| (time, SessionState::default()))
+                            .1
+                            .update(&packet);
                     }
                     Err(e) => {
                         warn!("Could not parse packet: {}", e);
@@ -519,257 +1050,508 @@ pub fn race2<P: AsRef<Path>, P2: AsRef<Path>>(file: P, out: Option<P2>) -> Resul
         }
     }
 
+    let sessions = sessions
+        .into_iter()
+        .sorted_by_key(|(_, (start_time, _))| *start_time)
+        .map(|(session_id, (offset, state))| {
+            let participants = state
+                .cars
+                .iter()
+                .enumerate()
+                .filter_map(|(i, car)| {
+                    // Only cars that were classified took part in the session
+                    let classification = car.classification.as_ref()?;
+                    let participant = car.participant.as_ref();
+
+                    Some(SessionParticipant {
+                        id: i as u64,
+                        ai_controlled: participant.is_some_and(|p| p.driver_id != 255),
+                        grid_position: classification.grid_position,
+                        position: classification.position,
+                        num_laps: classification.num_laps,
+                        status: match classification.status {
+                            ResultStatus::Finished => SessionParticipantStatus::Finished,
+                            ResultStatus::Disqualified => SessionParticipantStatus::DSQ,
+                            ResultStatus::DidNotFinish => SessionParticipantStatus::DNF,
+                            ResultStatus::Retired => SessionParticipantStatus::DNF,
+                            ResultStatus::NotClassified => SessionParticipantStatus::DNF,
+                            _ => SessionParticipantStatus::Unknown,
+                        },
+                        team: participant.map(|p| p.team).unwrap_or_default(),
+                        race_number: participant.map(|p| p.race_number).unwrap_or_default(),
+                        player: PlayerData {
+                            name: participant.map(|p| p.name.clone()).unwrap_or_default(),
+                            nationality: participant.and_then(|p| p.nationality),
+                        },
+                        num_pitstops: classification.num_pit_stops,
+                        tyre_stints: classification.tyre_stints.clone(),
+                        fastest_lap: classification.best_laptime.as_millis() as u64,
+                        total_time_without_penalties: classification
+                            .total_race_time_without_penalties
+                            .as_millis()
+                            as u64,
+                        penalty_time_in_s: classification.penalty_time_in_seconds,
+                        laps: car
+                            .laps
+                            .iter()
+                            .map(|lap| LapData {
+                                lap_number: lap.lap_number,
+                                lap_time: lap.lap_time.as_millis() as u64,
+                                sector_1_time: lap.sector_1_time.as_millis() as u64,
+                                sector_2_time: lap.sector_2_time.as_millis() as u64,
+                                sector_3_time: lap.sector_3_time.as_millis() as u64,
+                                lap_valid: lap.lap_valid,
+                                position: lap.position,
+                                safety_car: lap.safety_car,
+                                virtual_safety_car: lap.virtual_safety_car,
+                                formation: lap.formation,
+                                in_lap: lap.in_lap,
+                                out_lap: lap.out_lap,
+                                infringements: lap.infringements.clone(),
+                            })
+                            .collect_vec(),
+                        setup: car.setup,
+                    })
+                })
+                .sorted_by_key(|p| p.position) //TODO: not strictly needed I guess, but looks better when inspecting the json manually
+                .collect_vec();
+
+            let session = state.session.as_ref();
+
+            ParsedSessionData {
+                session_id,
+                session_link_id: session.map(|s| s.session_link_identifier).unwrap_or(0),
+                weekend_link_id: session.map(|s| s.weekend_link_identifier).unwrap_or(0),
+                season_link_id: session.map(|s| s.season_link_identifier).unwrap_or(0),
+                session_type: session
+                    .map(|s| s.session_type)
+                    .unwrap_or(SessionType::Unknown),
+                track: session.map(|s| s.track).unwrap_or(Track::Unknown),
+                start_time: header.as_ref().and_then(|header| {
+                    Some(header.start_time + chrono::Duration::from_std(offset).ok()?)
+                }),
+                participants,
+            }
+        })
+        .collect_vec();
+
+    Ok(sessions)
+}
+
+pub fn race2<P: AsRef<Path>, P2: AsRef<Path>>(file: P, out: Option<P2>) -> Result<(), eyre::Error> {
     let parsed_sessions = ParsedSessions {
-        sessions: sessions
-            .into_iter()
-            .map(|(session_id, session)| {
-                #[derive(Clone, Debug, Default)]
-                struct CurrentLapData {
-                    lap_num: u8,
-                    sector_1_time: Duration,
-                    sector_2_time: Duration,
-                    safety_car: bool,
-                    virtual_safety_car: bool,
-                    formation: bool,
-                    in_lap: bool,
-                    out_lap: bool,
-                    lap_valid: bool,
-                    infringements: Vec<Penalty>,
-                }
+        sessions: read_sessions(file)?,
+    };
 
-                #[derive(Clone, Debug, Default)]
-                struct DriverState {
-                    valid: bool,
-                    participant: SessionParticipant,
-                    session_history: Option<SessionHistoryPacket>,
-                    current_lap: Option<CurrentLapData>,
-                    pitting: bool,
-                }
+    let race_data_json = serde_json::to_string_pretty(&parsed_sessions)?;
 
-                struct State {
-                    session_type: SessionType,
-                    track: Track,
-                    length: SessionLength,
-                    session_link_id: u32,
-                    drivers: [DriverState; 22],
-                    safety_car_status: SafetyCarStatus,
-                }
+    if let Some(out) = out {
+        std::fs::write(&out, race_data_json)?;
+        println!("Wrote race data to {:?}", out.as_ref());
+    } else {
+        println!("{}", race_data_json);
+    }
 
-                let mut state = State {
-                    session_type: SessionType::Unknown,
-                    session_link_id: 0,
-                    track: Track::Unknown,
-                    length: SessionLength::None,
-                    drivers: Default::default(),
-                    safety_car_status: SafetyCarStatus::No,
-                };
+    Ok(())
+}
 
-                state
-                    .drivers
-                    .iter_mut()
-                    .enumerate()
-                    .for_each(|(i, driver)| driver.participant.id = i as u64);
-
-                session.packets.iter().for_each(|p| match &p.packet {
-                    Packet::Session(s) => {
-                        state.session_type = s.session_type;
-                        state.track = s.track;
-                        state.length = s.session_length;
-                        state.session_link_id = s.session_link_identifier;
-                        state.safety_car_status = s.safety_car_status;
-                    }
-                    Packet::LapData(ld) => {
-                        for (i, ld) in ld
-                            .lap_data
-                            .iter()
-                            .enumerate()
-                            .filter_map(|(i, ld)| ld.map(|ld| (i, ld)))
-                        {
-                            let driver_entry = &mut state.drivers[i];
-                            let current_lap = if let Some(mut current_lap) =
-                                driver_entry.current_lap.take()
-                            {
-                                if current_lap.lap_num < ld.current_lap_num {
-                                    // save current lap and start a new one
-                                    // TODO: inlap/outlap
-                                    let lap_time = ld.last_lap_time.as_millis() as u64;
-                                    let sector_1_time =
-                                        current_lap.sector_1_time.as_millis() as u64;
-                                    let sector_2_time =
-                                        current_lap.sector_2_time.as_millis() as u64;
-                                    let sector_3_time = lap_time
-                                        .saturating_sub(sector_1_time)
-                                        .saturating_sub(sector_2_time);
-
-                                    let lap = LapData {
-                                        lap_number: current_lap.lap_num,
-                                        lap_time,
-                                        sector_1_time,
-                                        sector_2_time,
-                                        sector_3_time,
-                                        lap_valid: current_lap.lap_valid,
-                                        position: ld.car_position,
-                                        safety_car: current_lap.safety_car,
-                                        virtual_safety_car: current_lap.virtual_safety_car,
-                                        formation: current_lap.formation,
-                                        in_lap: current_lap.in_lap,
-                                        out_lap: current_lap.out_lap,
-                                        infringements: current_lap.infringements,
-                                    };
-
-                                    driver_entry.participant.laps.push(lap);
-
-                                    // Start new lap
-                                    Some(CurrentLapData {
-                                        lap_num: ld.current_lap_num,
-                                        safety_car: state.safety_car_status
-                                            == SafetyCarStatus::Full,
-                                        virtual_safety_car: state.safety_car_status
-                                            == SafetyCarStatus::Virtual,
-                                        formation: state.safety_car_status
-                                            == SafetyCarStatus::Formation,
-                                        sector_1_time: ld.sector_1_time,
-                                        sector_2_time: ld.sector_2_time,
-                                        lap_valid: !ld.current_lap_invalid,
-                                        in_lap: false,
-                                        out_lap: current_lap.in_lap,
-                                        infringements: Vec::new(),
-                                    })
-                                } else if current_lap.lap_num == ld.current_lap_num {
-                                    // update current lap
-                                    current_lap.formation = current_lap.formation
-                                        || state.safety_car_status == SafetyCarStatus::Formation;
-                                    current_lap.safety_car = current_lap.safety_car
-                                        || state.safety_car_status == SafetyCarStatus::Full;
-                                    current_lap.virtual_safety_car = current_lap.virtual_safety_car
-                                        || state.safety_car_status == SafetyCarStatus::Virtual;
-                                    current_lap.sector_1_time = ld.sector_1_time;
-                                    current_lap.sector_2_time = ld.sector_2_time;
-                                    current_lap.lap_valid = !ld.current_lap_invalid;
-
-                                    if !driver_entry.pitting && ld.pit_status == PitStatus::Pitting
-                                    {
-                                        current_lap.in_lap = true;
-                                        driver_entry.pitting = true;
-                                    } else if driver_entry.pitting
-                                        && ld.pit_status == PitStatus::None
-                                    {
-                                        driver_entry.pitting = false;
-                                    }
-
-                                    Some(current_lap)
-                                } else {
-                                    // println!("What the fuck is this packet ??");
-                                    // println!("{:?}", ld);
-                                    Some(current_lap)
-                                }
-                            } else {
-                                Some(CurrentLapData {
-                                    lap_num: ld.current_lap_num,
-                                    safety_car: state.safety_car_status == SafetyCarStatus::Full,
-                                    virtual_safety_car: state.safety_car_status
-                                        == SafetyCarStatus::Virtual,
-                                    formation: state.safety_car_status
-                                        == SafetyCarStatus::Formation,
-                                    sector_1_time: ld.sector_1_time,
-                                    sector_2_time: ld.sector_2_time,
-                                    lap_valid: true,
-                                    in_lap: false,
-                                    out_lap: false,
-                                    infringements: Vec::new(),
-                                })
-                            };
-                            driver_entry.current_lap = current_lap;
-                        }
-                    }
-                    Packet::Event(e) => match e.event {
-                        Event::PenaltyIssued(p) => {
-                            // println!("penalty issued: {:?}", p);
-                            let driver_entry = &mut state.drivers[p.vehicle_idx as usize];
-                            if let Some(current_lap) = driver_entry.current_lap.as_mut() {
-                                current_lap.infringements.push(p);
-                                // println!("infringements: {:?}", current_lap.infringements);
-                            }
-                        }
-                        _ => {}
-                    },
-                    Packet::Participants(p) => {
-                        for (i, p) in p.participants.iter().enumerate() {
-                            let driver_entry = &mut state.drivers[i].participant;
-                            driver_entry.ai_controlled = p.driver_id != 255;
-                            driver_entry.player.name = p.name.clone();
-                            if p.name == "HULKENBERG" {
-                                println!("Hello, HULKENBERG! Session ID: {}", session_id);
-                            
-                            }
-                            
-                            driver_entry.player.nationality = p.nationality;
-                            driver_entry.race_number = p.race_number;
-                            driver_entry.team = p.team;
-                        }
-                    }
-                    Packet::FinalClassification(fc) => {
-                        for (i, cd) in fc
-                            .classification_data
-                            .iter()
-                            .enumerate()
-                            .filter_map(|(i, cd)| cd.as_ref().map(|cd| (i, cd)))
-                        {
-                            println!("{}: {:?}", i, cd);
-                            let driver_entry = &mut state.drivers[i].participant;
-                            driver_entry.grid_position = cd.grid_position;
-                            driver_entry.position = cd.position;
-                            driver_entry.fastest_lap = cd.best_laptime.as_millis() as u64;
-                            driver_entry.num_laps = cd.num_laps;
-                            driver_entry.num_pitstops = cd.num_pit_stops;
-                            driver_entry.penalty_time_in_s = cd.penalty_time_in_seconds;
-                            driver_entry.status = match cd.status {
-                                ResultStatus::Finished => SessionParticipantStatus::Finished,
-                                ResultStatus::Disqualified => SessionParticipantStatus::DSQ,
-                                ResultStatus::DidNotFinish => SessionParticipantStatus::DNF,
-                                ResultStatus::Retired => SessionParticipantStatus::DNF,
-                                ResultStatus::NotClassified => SessionParticipantStatus::DNF,
-                                _ => SessionParticipantStatus::Unknown,
-                            };
-                            driver_entry.total_time_without_penalties =
-                                cd.total_race_time_without_penalties.as_millis() as u64;
-                            driver_entry.tyre_stints = cd.tyre_stints.clone();
-                            state.drivers[i].valid = true;
+/// Groups the sessions of one or more recordings into a race weekend.
+///
+/// Sessions are ordered by when they started. For legacy recordings that time isn't known, so
+/// recordings are expected in the order they were made and sessions are ordered by recording.
+/// If the recordings contain several weekends, `weekend_id` picks one.
+pub fn weekend<P: AsRef<Path>, P2: AsRef<Path>>(
+    files: &[P],
+    out: Option<P2>,
+    weekend_id: Option<u32>,
+) -> Result<(), eyre::Error> {
+    let mut weekends: HashMap<(u32, u32), Vec<ParsedSessionData>> = HashMap::new();
+
+    for file in files {
+        for session in read_sessions(file)? {
+            let weekend = weekends
+                .entry((session.season_link_id, session.weekend_link_id))
+                .or_default();
+            // The same session can be in several recordings, the first one wins
+            if !weekend.iter().any(|s| s.session_id == session.session_id) {
+                weekend.push(session);
+            }
+        }
+    }
+
+    if let Some(weekend_id) = weekend_id {
+        weekends.retain(|(_, weekend_link_id), _| *weekend_link_id == weekend_id);
+    }
+
+    let ((season_link_id, weekend_link_id), mut sessions) = match weekends.len() {
+        0 => eyre::bail!("No sessions found"),
+        1 => weekends.into_iter().next().unwrap(),
+        _ => eyre::bail!(
+            "Found several weekends, pick one with --weekend-id: {}",
+            weekends
+                .keys()
+                .map(|(_, weekend_link_id)| weekend_link_id)
+                .sorted()
+                .join(", ")
+        ),
+    };
+
+    if sessions.iter().all(|s| s.start_time.is_some()) {
+        sessions.sort_by_key(|s| s.start_time);
+    }
+
+    let track = sessions
+        .iter()
+        .map(|s| s.track)
+        .find(|track| track.info().is_some())
+        .unwrap_or(Track::Unknown);
+
+    let entries = sessions
+        .iter()
+        .flat_map(|s| s.participants.iter())
+        .filter(|p| !p.ai_controlled)
+        .unique_by(|p| p.player.name.clone())
+        .map(|p| WeekendEntry {
+            driver: p.player.name.clone(),
+            team: p.team,
+        })
+        .collect_vec();
+
+    let weekend = ParsedWeekend {
+        season_link_id,
+        weekend_link_id,
+        name: track.name().to_owned(),
+        date: sessions.last().and_then(|s| s.start_time),
+        track,
+        track_id: track.info().map(|info| info.id.to_owned()),
+        entries,
+        sessions,
+    };
+
+    let weekend_json = serde_json::to_string_pretty(&weekend)?;
+
+    if let Some(out) = out {
+        std::fs::write(&out, weekend_json)?;
+        println!("Wrote weekend to {:?}", out.as_ref());
+    } else {
+        println!("{}", weekend_json);
+    }
+
+    Ok(())
+}
+
+pub fn bookmarks<P: AsRef<Path>, P2: AsRef<Path>>(
+    file: P,
+    out: Option<P2>,
+) -> Result<(), eyre::Error> {
+    let recording = RecordingReader::open(file)?;
+    debug!(header = ?recording.header(), "Opened recording");
+
+    let mut sessions: HashMap<u64, SessionState> = HashMap::new();
+    let mut buttons = ButtonTracker::new();
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+
+    for frame in recording {
+        let Frame {
+            time,
+            packet: packet_bytes,
+            ..
+        } = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                warn!("Could not read frame: {}", e);
+                break;
+            }
+        };
+        match decode_header(packet_bytes.clone()) {
+            Ok(header) => {
+                if ![PacketId::Session, PacketId::LapData, PacketId::Event]
+                    .contains(&header.packet_id)
+                {
+                    continue;
+                }
+                match decode_packet(packet_bytes) {
+                    Ok(packet) => {
+                        let session = sessions.entry(header.session_uid).or_default();
+                        session.update(&packet);
+
+                        let Packet::Event(event_packet) = packet else {
+                            continue;
+                        };
+                        let Event::Button(button) = event_packet.event else {
+                            continue;
+                        };
+                        for action in buttons.update(button).pressed.udp_actions() {
+                            bookmarks.push(Bookmark {
+                                time,
+                                session_id: header.session_uid,
+                                session_time: header.session_time,
+                                session_type: session
+                                    .session
+                                    .as_ref()
+                                    .map(|s| s.session_type)
+                                    .unwrap_or(SessionType::Unknown),
+                                track: session
+                                    .session
+                                    .as_ref()
+                                    .map(|s| s.track)
+                                    .unwrap_or(Track::Unknown),
+                                action,
+                                lap_number: session
+                                    .player_car()
+                                    .and_then(|car| car.current_lap.as_ref())
+                                    .map(|lap| lap.lap_number),
+                            });
                         }
                     }
-                    Packet::SessionHistory(sh) => {
-                        state.drivers[sh.car_index as usize].session_history = Some(sh.clone());
-                        state.drivers[sh.car_index as usize].participant.session_history = sh.lap_history_data.clone();
+                    Err(e) => {
+                        warn!("Could not parse packet: {}", e);
                     }
-                    _ => {}
-                });
-
-                state.drivers.sort_by_key(|d| d.participant.position); //TODO: not strictly needed I guess, but looks better when inspecting the json manually
-                let participants: Vec<SessionParticipant> = state
-                    .drivers
-                    .into_iter()
-                    .filter_map(|ds| if ds.valid { Some(ds.participant) } else { None })
-                    .collect_vec();
-
-                ParsedSessionData {
-                    session_id,
-                    session_link_id: state.session_link_id,
-                    session_type: state.session_type,
-                    track: state.track,
-                    participants,
                 }
-            })
-            .collect_vec(),
-    };
+            }
+            Err(e) => {
+                warn!("Could not parse header: {}", e);
+            }
+        }
+    }
 
-    let race_data_json = serde_json::to_string_pretty(&parsed_sessions)?;
+    let bookmarks_json = serde_json::to_string_pretty(&bookmarks)?;
 
     if let Some(out) = out {
-        std::fs::write(&out, race_data_json)?;
-        println!("Wrote race data to {:?}", out.as_ref());
+        std::fs::write(&out, bookmarks_json)?;
+        println!("Wrote bookmarks to {:?}", out.as_ref());
     } else {
-        println!("{}", race_data_json);
+        println!("{}", bookmarks_json);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::{BufMut, BytesMut};
+    use telemetry::test_util;
+
+    use super::*;
+
+    const SESSION_UID: u64 = 4242;
+    const SESSION: u8 = 1;
+    const LAP_DATA: u8 = 2;
+    const EVENT: u8 = 3;
+    const PARTICIPANTS: u8 = 4;
+    const CAR_SETUPS: u8 = 5;
+    const FINAL_CLASSIFICATION: u8 = 8;
+    const SESSION_HISTORY: u8 = 11;
+
+    /// A race of three cars over three laps, in a legacy recording without a header.
+    ///
+    /// The second car overtakes the first on lap 2, where the third car pits and the second car
+    /// gets a penalty. The virtual safety car is out at the start of lap 3.
+    struct RaceRecording {
+        buf: BytesMut,
+        time: f64,
+    }
+
+    impl RaceRecording {
+        fn new() -> Self {
+            Self {
+                buf: BytesMut::new(),
+                time: 0.0,
+            }
+        }
+
+        /// An F1 23 packet of `size` bytes with the header filled in and the rest zeroed
+        fn packet(packet_id: u8, size: usize) -> BytesMut {
+            test_util::packet(2023, packet_id, SESSION_UID, 0, 0, size)
+        }
+
+        fn write(&mut self, packet: BytesMut) {
+            self.time += 0.5;
+            self.buf.put_u64(packet.len() as u64);
+            self.buf.put_f64(self.time);
+            self.buf.put(packet);
+        }
+
+        fn session(&mut self, safety_car_status: u8) {
+            let mut packet = Self::packet(SESSION, 644);
+            packet[32] = 3; // Total laps
+            packet[35] = 10; // Race
+            packet[36] = 7; // Silverstone
+            packet[153] = safety_car_status;
+            packet[614..618].copy_from_slice(&77_u32.to_le_bytes());
+            self.write(packet);
+        }
+
+        fn participants(&mut self) {
+            let mut packet = Self::packet(PARTICIPANTS, 1306);
+            packet[29] = 3;
+            for (i, name) in ["ALPHA", "BRAVO", "CHARLIE"].iter().enumerate() {
+                let entry = 30 + i * 58;
+                let ai_controlled = name == &"CHARLIE";
+                packet[entry] = ai_controlled.into();
+                packet[entry + 1] = if ai_controlled { 9 } else { 255 };
+                packet[entry + 3] = i as u8; // Team
+                packet[entry + 5] = 30 + i as u8; // Race number
+                packet[entry + 7..entry + 7 + name.len()].copy_from_slice(name.as_bytes());
+            }
+            self.write(packet);
+        }
+
+        fn car_setups(&mut self) {
+            let mut packet = Self::packet(CAR_SETUPS, 1107);
+            for car in 0..3 {
+                packet[29 + car * 49] = 20 + car as u8; // Front wing
+            }
+            self.write(packet);
+        }
+
+        /// Lap data of all three cars while driving `sector` of `lap`
+        fn lap_data(&mut self, lap: u8, sector: u8) {
+            let mut packet = Self::packet(LAP_DATA, 1131);
+            for car in 0..3 {
+                let entry = &mut packet[29 + car * 50..29 + (car + 1) * 50];
+                let sector_1_ms = 30_000 + 100 * car as u16 + lap as u16;
+                let sector_2_ms = 30_500 + 100 * car as u16;
+                let last_lap_ms = if lap > 1 {
+                    90_000 + 300 * car as u32 + lap as u32
+                } else {
+                    0
+                };
+                let overtaken = lap > 2 || (lap == 2 && sector > 0);
+                let position = match car {
+                    0 if overtaken => 2,
+                    1 if overtaken => 1,
+                    _ => car as u8 + 1,
+                };
+                let pit_status = match (car, lap, sector) {
+                    (2, 2, 1) => 1,
+                    (2, 2, 2) => 2,
+                    _ => 0,
+                };
+
+                entry[0..4].copy_from_slice(&last_lap_ms.to_le_bytes());
+                if sector > 0 {
+                    entry[8..10].copy_from_slice(&sector_1_ms.to_le_bytes());
+                }
+                if sector > 1 {
+                    entry[11..13].copy_from_slice(&sector_2_ms.to_le_bytes());
+                }
+                entry[30] = position;
+                entry[31] = lap;
+                entry[32] = pit_status;
+                entry[34] = sector.min(2);
+                entry[35] = (car == 0 && lap == 1 && sector > 1).into(); // Lap invalid
+                entry[43] = if lap > 3 { 3 } else { 2 }; // Active, then finished
+            }
+            self.write(packet);
+        }
+
+        fn penalty(&mut self, car: u8) {
+            let mut packet = Self::packet(EVENT, 45);
+            packet[29..33].copy_from_slice(b"PENA");
+            packet[33] = 4; // Time penalty
+            packet[34] = 7; // Corner cutting gained time
+            packet[35] = car;
+            packet[36] = 255;
+            packet[37] = 5;
+            packet[38] = 2;
+            self.write(packet);
+        }
+
+        fn button(&mut self) {
+            let mut packet = Self::packet(EVENT, 45);
+            packet[29..33].copy_from_slice(b"BUTN");
+            packet[33] = 1;
+            self.write(packet);
+        }
+
+        fn session_history(&mut self, car: u8) {
+            let mut packet = Self::packet(SESSION_HISTORY, 1460);
+            packet[29] = car;
+            packet[30] = 1;
+            packet[36..40].copy_from_slice(&90_000_u32.to_le_bytes());
+            self.write(packet);
+        }
+
+        fn final_classification(&mut self) {
+            let mut packet = Self::packet(FINAL_CLASSIFICATION, 1020);
+            packet[29] = 3;
+            for (car, position) in [(0, 2), (1, 1), (2, 3)] {
+                let entry = &mut packet[30 + car * 45..30 + (car + 1) * 45];
+                let pit_stops = (car == 2) as u8;
+                entry[0] = position;
+                entry[1] = 3; // Laps
+                entry[2] = car as u8 + 1; // Grid position
+                entry[3] = [18, 25, 15][car];
+                entry[4] = pit_stops;
+                entry[5] = 3; // Finished
+                entry[6..10].copy_from_slice(&(90_002 + 300 * car as u32).to_le_bytes());
+                entry[10..18].copy_from_slice(&(272.5 + car as f64).to_le_bytes());
+                entry[18] = if car == 1 { 5 } else { 0 };
+                entry[19] = (car == 1) as u8;
+                entry[20] = 1 + pit_stops; // Tyre stints
+                entry[21] = 18; // C3
+                entry[22] = 19; // C2
+                entry[29] = 17; // Medium
+                entry[30] = 18; // Hard
+                entry[37] = if car == 2 { 2 } else { 3 };
+                entry[38] = 3;
+            }
+            self.write(packet);
+        }
+    }
+
+    fn race() -> BytesMut {
+        let mut race = RaceRecording::new();
+        race.session(0);
+        race.participants();
+        race.car_setups();
+        for lap in 1..=3 {
+            if lap == 3 {
+                race.session(2);
+            }
+            for sector in 0..3 {
+                race.lap_data(lap, sector);
+                if lap == 2 && sector == 1 {
+                    race.penalty(1);
+                    race.button();
+                }
+            }
+            if lap == 3 {
+                race.session(0);
+            }
+        }
+        race.lap_data(4, 0);
+        for car in 0..3 {
+            race.session_history(car);
+        }
+        race.final_classification();
+        race.buf
+    }
+
+    /// `race2` gives the same output as it did before it was built on `SessionState`
+    #[test]
+    fn race_output_is_unchanged() {
+        let path =
+            std::env::temp_dir().join(format!("telemetry-cli-race2-{}.bin", std::process::id()));
+        std::fs::write(&path, race()).unwrap();
+        let sessions = read_sessions(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let mut sessions = serde_json::to_value(ParsedSessions {
+            sessions: sessions.unwrap(),
+        })
+        .unwrap();
+        // Added to the output since
+        for session in sessions["sessions"].as_array_mut().unwrap() {
+            let session = session.as_object_mut().unwrap();
+            for key in ["weekend_link_id", "season_link_id", "start_time"] {
+                session.remove(key);
+            }
+        }
+
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/race2.json")).unwrap();
+        assert_eq!(sessions, expected);
+    }
+}
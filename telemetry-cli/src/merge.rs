@@ -0,0 +1,688 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    time::Duration,
+};
+
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use telemetry::{
+    decode_header, decode_packet,
+    packet::{
+        event::Event,
+        header::{Format, PacketId},
+        participants::Telemetry,
+        Packet,
+    },
+};
+use tracing::{debug, warn};
+
+use crate::recording::{RecordingHeader, RecordingReader, RecordingWriter};
+
+/// Combines recordings that different players in a lobby made of the same sessions into one.
+///
+/// Every recording misses some packets, so packets are matched up by their overall frame
+/// identifier and packet type and each one is written once. Packets that are about a single car,
+/// the player's motion ex packets and events are also told apart by their car or contents, as the
+/// game sends several of those in a frame.
+///
+/// Cars with restricted telemetry have their setup, status and damage zeroed in the recordings of
+/// other players, so for those cars the data is taken from the recording where they are the
+/// player. Only the bytes of those cars are copied over, so the packets stay in the format of
+/// the game that sent them. This is only done for F1 23 and F1 24, where the layout is known.
+///
+/// Every packet of the merged sessions is kept in memory until the merge is written, along with
+/// the copies from the other recordings that can still be needed: the ones with restricted cars
+/// filled in and the ones about the recording's own car. The merge needs about as much memory as
+/// the largest recording plus those copies, so use a session id to merge long recordings one
+/// session at a time.
+pub struct RecordingMerger {
+    session_uid: Option<u64>,
+    recordings: Vec<Source>,
+    /// In the order they were first seen
+    packets: Vec<MergedPacket>,
+    /// Index into `packets`
+    keys: HashMap<PacketKey, usize>,
+}
+
+/// A recording that was added
+struct Source {
+    start_time: DateTime<Utc>,
+    address: String,
+    /// Cars that were driven by the player, per session
+    players: HashMap<u64, HashSet<u8>>,
+    /// Cars that had restricted telemetry in this recording, per session
+    restricted: HashMap<u64, HashSet<u8>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct PacketKey {
+    session_uid: u64,
+    frame: u32,
+    packet_id: PacketId,
+    part: Part,
+}
+
+/// Tells the packets of the same type in a frame apart
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Part {
+    Whole,
+    /// Session history and tyre sets are sent for one car at a time
+    Car(u8),
+    /// Motion ex packets are only about the player's car
+    Player(u8),
+    /// Several events can happen in one frame, they're told apart by their code and the cars and
+    /// values they're about (see [`event_details`])
+    Event([u8; 4], Vec<u32>),
+}
+
+struct MergedPacket {
+    key: PacketKey,
+    /// When the packet was first received, the clocks of the recordings are never quite the same
+    time: DateTime<Utc>,
+    /// Recording and frame in it where the packet was first seen, to keep the order the game
+    /// sent the packets of a frame in
+    order: (usize, usize),
+    /// Where the data of each car is, for packets that can have cars with restricted telemetry
+    cars: Option<CarLayout>,
+    /// Every recording that has the packet, in the order they were added
+    recordings: Vec<usize>,
+    /// The copies that can end up in the merged recording. The first one and, for packets with
+    /// the data of cars with restricted telemetry or about one car, those of other recordings.
+    copies: Vec<Received>,
+}
+
+/// Offset of the first car's data in a packet, and the size of each car's data
+#[derive(Clone, Copy, Debug)]
+struct CarLayout {
+    offset: usize,
+    size: usize,
+}
+
+struct Received {
+    recording: usize,
+    port: Option<u16>,
+    packet: Bytes,
+}
+
+/// What the merge did with a session
+#[derive(Clone, Debug)]
+pub struct SessionReport {
+    pub session_uid: u64,
+    pub packets: usize,
+    /// Number of added recordings the session is in
+    pub recordings: usize,
+    /// Packets that were in more than one recording
+    pub duplicates: usize,
+    /// Packets where cars with restricted telemetry were filled in from another recording
+    pub completed: usize,
+    /// Packets that were only in that recording, for every added recording
+    pub only_in: Vec<usize>,
+}
+
+impl RecordingMerger {
+    /// Only merges the session with `session_uid`, or every session if it's `None`
+    pub fn new(session_uid: Option<u64>) -> Self {
+        Self {
+            session_uid,
+            recordings: Vec::new(),
+            packets: Vec::new(),
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Reads every packet of a recording, recordings that are added first are preferred when a
+    /// packet is in several of them
+    pub fn add<P: AsRef<Path>>(&mut self, path: P) -> eyre::Result<()> {
+        let path = path.as_ref();
+        let reader = RecordingReader::open(path)?;
+        let Some(header) = reader.header() else {
+            eyre::bail!(
+                "{:?} is a legacy recording, it has no start time to line it up",
+                path
+            );
+        };
+        debug!(?header, ?path, "Merging recording");
+
+        let recording = self.recordings.len();
+        let mut source = Source {
+            start_time: header.start_time,
+            address: header.address.clone(),
+            players: HashMap::new(),
+            restricted: HashMap::new(),
+        };
+
+        for (position, frame) in reader.enumerate() {
+            let frame = match frame {
+                Ok(frame) => frame,
+                Err(e) => {
+                    warn!("Could not read frame: {}", e);
+                    break;
+                }
+            };
+            let Ok(header) = decode_header(frame.packet.clone()) else {
+                continue;
+            };
+            let session_uid = header.session_uid;
+            if session_uid == 0 || self.session_uid.is_some_and(|uid| uid != session_uid) {
+                continue;
+            }
+            let Some(frame_identifier) = header.overall_frame_identifier else {
+                eyre::bail!(
+                    "{:?} has F1 22 packets, which have no overall frame identifier",
+                    path
+                );
+            };
+
+            let players = source.players.entry(session_uid).or_default();
+            players.insert(header.player_car_index);
+            players.extend(header.secondary_player_car_index);
+
+            let part = match header.packet_id {
+                PacketId::MotionEx => Part::Player(header.player_car_index),
+                PacketId::SessionHistory
+                | PacketId::TyreSets
+                | PacketId::Event
+                | PacketId::Participants => match decode_packet(frame.packet.clone()) {
+                    Ok(Packet::SessionHistory(packet)) => Part::Car(packet.car_index),
+                    Ok(Packet::TyreSets(packet)) => Part::Car(packet.car_index),
+                    Ok(Packet::Event(packet)) => {
+                        let mut code = [0; 4];
+                        code.copy_from_slice(&frame.packet[EVENT_CODE]);
+                        Part::Event(code, event_details(&packet.event))
+                    }
+                    Ok(Packet::Participants(packet)) => {
+                        source.restricted.entry(session_uid).or_default().extend(
+                            packet
+                                .participants
+                                .iter()
+                                .positions(|participant| {
+                                    matches!(participant.your_telemetry, Telemetry::Restricted)
+                                })
+                                .map(|car| car as u8),
+                        );
+                        Part::Whole
+                    }
+                    // Can't be told apart from others, but still keep it
+                    _ => Part::Whole,
+                },
+                _ => Part::Whole,
+            };
+
+            let key = PacketKey {
+                session_uid,
+                frame: frame_identifier,
+                packet_id: header.packet_id,
+                part,
+            };
+            let time = source.start_time + chrono::Duration::from_std(frame.time)?;
+            let cars = car_layout(header.format, header.packet_id);
+            let received = Received {
+                recording,
+                port: frame.port,
+                packet: frame.packet,
+            };
+            match self.keys.get(&key) {
+                Some(&i) => {
+                    let packet = &mut self.packets[i];
+                    packet.time = packet.time.min(time);
+                    packet.recordings.push(recording);
+                    let needed = match key.part {
+                        Part::Car(car) => players.contains(&car),
+                        _ => packet.cars.is_some(),
+                    };
+                    if needed {
+                        packet.copies.push(received);
+                    }
+                }
+                None => {
+                    self.keys.insert(key.clone(), self.packets.len());
+                    self.packets.push(MergedPacket {
+                        key,
+                        time,
+                        order: (recording, position),
+                        cars,
+                        recordings: vec![recording],
+                        copies: vec![received],
+                    });
+                }
+            }
+        }
+
+        self.recordings.push(source);
+        Ok(())
+    }
+
+    /// Writes the merged recording, session by session in the order they started
+    pub fn write<P: AsRef<Path>>(
+        mut self,
+        path: P,
+        compress: bool,
+    ) -> eyre::Result<Vec<SessionReport>> {
+        let Some(start_time) = self.recordings.iter().map(|source| source.start_time).min() else {
+            eyre::bail!("No recordings to merge");
+        };
+        if self.packets.is_empty() {
+            eyre::bail!("No sessions found");
+        }
+        let mut header = RecordingHeader::new(
+            &self
+                .recordings
+                .iter()
+                .flat_map(|source| source.address.split(','))
+                .filter(|address| !address.is_empty())
+                .unique()
+                .join(","),
+        );
+        header.start_time = start_time;
+
+        let mut session_starts: HashMap<u64, DateTime<Utc>> = HashMap::new();
+        for packet in &self.packets {
+            let start = session_starts
+                .entry(packet.key.session_uid)
+                .or_insert(packet.time);
+            *start = (*start).min(packet.time);
+        }
+        let mut packets = std::mem::take(&mut self.packets);
+        packets.sort_by_key(|packet| {
+            (
+                session_starts[&packet.key.session_uid],
+                packet.key.session_uid,
+                packet.key.frame,
+                packet.order,
+            )
+        });
+
+        let mut writer = RecordingWriter::create(&path, Some(&header), compress)?;
+        let mut reports: Vec<SessionReport> = Vec::new();
+        let mut last_time = Duration::ZERO;
+        for packet in &packets {
+            let (port, bytes, completed) = self.pick(packet);
+            // Keep the times in order, even though they come from different clocks
+            let time = (packet.time - start_time).to_std().unwrap_or_default();
+            last_time = last_time.max(time);
+            writer.write_frame(last_time, port, &bytes)?;
+
+            let report = match reports.last_mut() {
+                Some(report) if report.session_uid == packet.key.session_uid => report,
+                _ => {
+                    let session_uid = packet.key.session_uid;
+                    reports.push(SessionReport {
+                        session_uid,
+                        packets: 0,
+                        recordings: self
+                            .recordings
+                            .iter()
+                            .filter(|source| source.players.contains_key(&session_uid))
+                            .count(),
+                        duplicates: 0,
+                        completed: 0,
+                        only_in: vec![0; self.recordings.len()],
+                    });
+                    reports.last_mut().unwrap()
+                }
+            };
+            report.packets += 1;
+            report.duplicates += packet.recordings.len() - 1;
+            report.completed += completed as usize;
+            if let [recording] = packet.recordings.as_slice() {
+                report.only_in[*recording] += 1;
+            }
+        }
+        writer.finish()?;
+
+        Ok(reports)
+    }
+
+    /// The copy of a packet to write, with the cars that have restricted telemetry in it filled
+    /// in from the recordings where they are the player. Also returns whether any were.
+    fn pick(&self, packet: &MergedPacket) -> (Option<u16>, Bytes, bool) {
+        let session_uid = packet.key.session_uid;
+        let players = |copy: &Received| self.recordings[copy.recording].players.get(&session_uid);
+        let first = &packet.copies[0];
+        let unchanged = |copy: &Received| (copy.port, copy.packet.clone(), false);
+
+        match packet.key.part {
+            // The car's own recording has all of its data
+            Part::Car(car) => {
+                let own = packet
+                    .copies
+                    .iter()
+                    .find(|copy| players(copy).is_some_and(|players| players.contains(&car)))
+                    .unwrap_or(first);
+                return unchanged(own);
+            }
+            Part::Whole => {}
+            _ => return unchanged(first),
+        }
+
+        let Some(CarLayout { offset, size }) = packet.cars else {
+            return unchanged(first);
+        };
+        let Some(restricted) = self.recordings[first.recording]
+            .restricted
+            .get(&session_uid)
+        else {
+            return unchanged(first);
+        };
+
+        let mut merged = BytesMut::from(&first.packet[..]);
+        let mut completed = false;
+        for copy in &packet.copies[1..] {
+            // The game sends the same packet to everyone, only the restricted cars differ
+            if copy.packet.len() != merged.len() {
+                continue;
+            }
+            let Some(players) = players(copy) else {
+                continue;
+            };
+            for &car in players.intersection(restricted) {
+                let car = offset + car as usize * size..offset + (car as usize + 1) * size;
+                if car.end <= merged.len() {
+                    merged[car.clone()].copy_from_slice(&copy.packet[car]);
+                    completed = true;
+                }
+            }
+        }
+
+        if completed {
+            (first.port, merged.freeze(), true)
+        } else {
+            unchanged(first)
+        }
+    }
+}
+
+/// Where the events' codes are in event packets
+const EVENT_CODE: std::ops::Range<usize> = 29..33;
+
+/// Where the data of each car is in the packets that have it zeroed for cars with restricted
+/// telemetry, `None` for other packets and games
+fn car_layout(format: Format, packet_id: PacketId) -> Option<CarLayout> {
+    // Right after the header, participants start with the number of active cars
+    let (offset, size) = match (format, packet_id) {
+        (Format::TwentyThree, PacketId::CarSetups) => (29, 49),
+        (Format::TwentyFour, PacketId::CarSetups) => (29, 50),
+        (Format::TwentyThree | Format::TwentyFour, PacketId::CarStatus) => (29, 55),
+        (Format::TwentyThree | Format::TwentyFour, PacketId::CarDamage) => (29, 42),
+        (Format::TwentyThree, PacketId::Participants) => (30, 58),
+        (Format::TwentyFour, PacketId::Participants) => (30, 60),
+        _ => return None,
+    };
+    Some(CarLayout { offset, size })
+}
+
+/// The cars and values an event is about, which tell apart events with the same code in a frame
+fn event_details(event: &Event) -> Vec<u32> {
+    match event {
+        Event::FastestLap(lap) => vec![lap.vehicle_idx as u32, lap.lap_time.as_micros() as u32],
+        Event::Retirement(car)
+        | Event::TeamMateInPits(car)
+        | Event::RaceWinner(car)
+        | Event::DriveThroughServed(car) => vec![car.0 as u32],
+        Event::PenaltyIssued(penalty) => vec![
+            penalty.vehicle_idx as u32,
+            penalty.other_vehicle_idx as u32,
+            penalty.penalty_type as u32,
+            penalty.infringement_type as u32,
+            penalty.time as u32,
+            penalty.lap_num as u32,
+            penalty.places_gained as u32,
+        ],
+        Event::SpeedTrapTriggered(trap) => vec![trap.vehicle_idx as u32, trap.speed.to_bits()],
+        Event::StartLights(lights) => vec![lights.num_lights as u32],
+        Event::Flashback(flashback) => vec![
+            flashback.flashback_frame_identifier,
+            flashback.flashback_session_time.to_bits(),
+        ],
+        Event::Button(buttons) => vec![buttons.button_status.bits()],
+        Event::Overtake(overtake) => vec![
+            overtake.overtaking_vehicle_idx as u32,
+            overtake.being_overtaken_vehicle_idx as u32,
+        ],
+        Event::SafetyCar(safety_car) => vec![
+            safety_car.safety_car_type as u32,
+            safety_car.event_type as u32,
+        ],
+        Event::Collision(collision) => vec![
+            collision.vehicle_1_idx as u32,
+            collision.vehicle_2_idx as u32,
+        ],
+        Event::Unknown(event) => event.data.iter().map(|&byte| byte as u32).collect(),
+        Event::SessionStarted
+        | Event::SessionEnded
+        | Event::DRSEnabled
+        | Event::DRSDisabled
+        | Event::ChequeredFlag
+        | Event::LightsOut
+        | Event::StopGoServed
+        | Event::RedFlag => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::TimeZone;
+    use telemetry::test_util::packet;
+
+    use super::*;
+    use crate::{recording::Frame, test_util::TempDir};
+
+    const SESSION_UID: u64 = 777;
+    const EVENT: u8 = 3;
+    const PARTICIPANTS: u8 = 4;
+    const CAR_STATUS: u8 = 7;
+
+    /// Writes a recording with a packet every frame, frames are 10ms apart
+    fn recording(dir: &TempDir, name: &str, packets: &[(u32, Bytes)]) -> PathBuf {
+        let mut header = RecordingHeader::new("0.0.0.0:20777");
+        header.start_time = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let frames = packets
+            .iter()
+            .map(|(frame, packet)| (Duration::from_millis(10 * *frame as u64), packet.clone()));
+        dir.recording(name, Some(&header), frames)
+    }
+
+    fn merge(dir: &TempDir, recordings: &[PathBuf]) -> (Vec<Frame>, Vec<SessionReport>) {
+        let mut merger = RecordingMerger::new(None);
+        for recording in recordings {
+            merger.add(recording).unwrap();
+        }
+        let path = dir.path().join("merged.bin");
+        let reports = merger.write(&path, false).unwrap();
+        let frames = RecordingReader::open(&path)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        (frames, reports)
+    }
+
+    /// Two cars, with `restricted` having restricted telemetry
+    fn participants(format: u16, frame: u32, player: u8, restricted: u8) -> (u32, Bytes) {
+        let (size, stride) = if format == 2023 {
+            (1306, 58)
+        } else {
+            (1350, 60)
+        };
+        let mut packet = packet(format, PARTICIPANTS, SESSION_UID, frame, player, size);
+        packet[29] = 2;
+        for car in 0..2 {
+            packet[30 + car * stride + 55] = (car != restricted as usize) as u8;
+        }
+        (frame, packet.freeze())
+    }
+
+    /// Only the car of the player has its status, its fuel in the tank is 10 plus its index
+    fn car_status(format: u16, frame: u32, player: u8) -> (u32, Bytes) {
+        let mut packet = packet(format, CAR_STATUS, SESSION_UID, frame, player, 1239);
+        let fuel = 29 + player as usize * 55 + 5;
+        packet[fuel..fuel + 4].copy_from_slice(&(10.0 + player as f32).to_le_bytes());
+        (frame, packet.freeze())
+    }
+
+    fn overtake(frame: u32, player: u8, overtaking: u8, overtaken: u8) -> (u32, Bytes) {
+        let mut packet = packet(2023, EVENT, SESSION_UID, frame, player, 45);
+        packet[29..33].copy_from_slice(b"OVTK");
+        packet[33] = overtaking;
+        packet[34] = overtaken;
+        (frame, packet.freeze())
+    }
+
+    fn decode(frame: &Frame) -> Packet {
+        decode_packet(frame.packet.clone()).unwrap()
+    }
+
+    /// Two players that see each other's telemetry as restricted
+    fn restricted_cars_are_completed(format: u16) -> Vec<Frame> {
+        let dir = TempDir::new(&format!("merge-restricted_{format}"));
+        let write = |name, player| {
+            let mut packets = vec![participants(format, 1, player, 1 - player)];
+            packets.extend((1..=3).map(|frame| car_status(format, frame, player)));
+            recording(&dir, name, &packets)
+        };
+        let a = write("a.bin", 0);
+        let b = write("b.bin", 1);
+
+        let (frames, reports) = merge(&dir, &[a, b]);
+        let [report] = reports.as_slice() else {
+            panic!("expected one session, got {reports:?}");
+        };
+        assert_eq!(report.packets, 4);
+        assert_eq!(report.duplicates, 4);
+        assert_eq!(report.completed, 4);
+
+        let Packet::Participants(participants) = decode(&frames[0]) else {
+            panic!("expected participants first");
+        };
+        assert!(participants.participants[..2]
+            .iter()
+            .all(|participant| matches!(participant.your_telemetry, Telemetry::Public)));
+        for frame in &frames[1..] {
+            let Packet::CarStatus(status) = decode(frame) else {
+                panic!("expected car status");
+            };
+            assert_eq!(status.header.player_car_index, 0);
+            assert_eq!(status.car_status_data[0].fuel_in_tank, 10.0);
+            assert_eq!(status.car_status_data[1].fuel_in_tank, 11.0);
+        }
+        frames
+    }
+
+    #[test]
+    fn restricted_cars_are_completed_f1_23() {
+        for frame in restricted_cars_are_completed(2023) {
+            assert!(matches!(
+                decode_header(frame.packet).unwrap().format,
+                Format::TwentyThree
+            ));
+        }
+    }
+
+    #[test]
+    fn restricted_cars_are_completed_f1_24() {
+        for frame in restricted_cars_are_completed(2024) {
+            assert!(matches!(
+                decode_header(frame.packet).unwrap().format,
+                Format::TwentyFour
+            ));
+        }
+    }
+
+    #[test]
+    fn duplicates_are_written_once() {
+        let dir = TempDir::new("merge-duplicates");
+        let write = |name, player| {
+            recording(
+                &dir,
+                name,
+                &[
+                    car_status(2023, 1, player),
+                    overtake(2, player, 0, 1),
+                    overtake(2, player, 1, 0),
+                    overtake(3, player, 0, 1),
+                ],
+            )
+        };
+        let a = write("a.bin", 0);
+        let b = write("b.bin", 1);
+
+        let (frames, reports) = merge(&dir, &[a, b]);
+        assert_eq!(reports[0].packets, 4);
+        assert_eq!(reports[0].duplicates, 4);
+        assert_eq!(reports[0].only_in, [0, 0]);
+        // Without participants neither recording knows of restricted cars
+        assert_eq!(reports[0].completed, 0);
+
+        let overtakes = frames[1..]
+            .iter()
+            .map(|frame| {
+                let Packet::Event(event) = decode(frame) else {
+                    panic!("expected an event");
+                };
+                let Event::Overtake(overtake) = event.event else {
+                    panic!("expected an overtake");
+                };
+                (
+                    event.header.overall_frame_identifier.unwrap(),
+                    overtake.overtaking_vehicle_idx,
+                )
+            })
+            .collect_vec();
+        assert_eq!(overtakes, [(2, 0), (2, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn packets_are_in_frame_order() {
+        let dir = TempDir::new("merge-order");
+        let a = recording(
+            &dir,
+            "a.bin",
+            &[
+                car_status(2023, 1, 0),
+                overtake(3, 0, 0, 1),
+                car_status(2023, 3, 0),
+                car_status(2023, 5, 0),
+            ],
+        );
+        let b = recording(
+            &dir,
+            "b.bin",
+            &[
+                car_status(2023, 2, 1),
+                car_status(2023, 3, 1),
+                overtake(3, 1, 0, 1),
+                car_status(2023, 4, 1),
+            ],
+        );
+
+        let (frames, reports) = merge(&dir, &[a, b]);
+        assert_eq!(reports[0].only_in, [2, 2]);
+        let order = frames
+            .iter()
+            .map(|frame| {
+                let packet = decode(frame);
+                let header = packet.header();
+                (header.overall_frame_identifier.unwrap(), header.packet_id)
+            })
+            .collect_vec();
+        assert_eq!(
+            order,
+            [
+                (1, PacketId::CarStatus),
+                (2, PacketId::CarStatus),
+                // In the order of the recording that was added first
+                (3, PacketId::Event),
+                (3, PacketId::CarStatus),
+                (4, PacketId::CarStatus),
+                (5, PacketId::CarStatus),
+            ]
+        );
+        assert!(frames
+            .iter()
+            .tuple_windows()
+            .all(|(frame, next)| frame.time <= next.time));
+    }
+}
@@ -0,0 +1,595 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, Chain, Cursor, Read, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use self::index::RecordingIndex;
+
+pub mod index;
+pub mod repair;
+
+/// Start of every recording, legacy recordings start straight away with the first frame
+pub const MAGIC: &[u8; 8] = b"F1TELREC";
+pub const VERSION: u16 = 3;
+
+/// Size and time in front of every packet, followed by the port since version 2
+const FRAME_HEADER_SIZE: u64 = 16;
+/// Size and checksum after every packet since version 3
+const FRAME_TRAILER_SIZE: u64 = 8;
+/// Frames bigger than this can't be a packet, so the recording must be corrupt
+const MAX_FRAME_SIZE: u64 = 64 * 1024;
+
+/// Start of a zstd frame, used to detect compressed recordings
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// How much recording time a compressed recording buffers before flushing (and the index is
+/// saved), this is at most what is lost when the recorder crashes
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Describes a recording, written once at the start of the file.
+///
+/// Layout (big endian, like the frames): magic, `u16` version, `i64` start time in microseconds
+/// since the unix epoch, and the listen address and tool version as `u16` length prefixed strings.
+///
+/// Frames are `u64` size, `f64` seconds since the start and the datagram. Since version 2 the
+/// `u16` port the datagram was received on (0 if unknown) comes right before the datagram. Since
+/// version 3 every frame ends with the size again as a `u32` and a CRC32 of everything before it
+/// in the frame, so a frame that was cut off or damaged is never mistaken for a packet.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub version: u16,
+    /// When the recording started, frame times are relative to this
+    pub start_time: DateTime<Utc>,
+    /// Addresses the recorder listened on, separated by commas
+    pub address: String,
+    /// Version of the tool that made the recording
+    pub tool_version: String,
+}
+
+/// A datagram as it was received
+#[derive(Clone, Debug)]
+pub struct Frame {
+    /// Time since the start of the recording
+    pub time: Duration,
+    /// Port the datagram was received on, not known for recordings before version 2
+    pub port: Option<u16>,
+    pub packet: Bytes,
+}
+
+impl RecordingHeader {
+    pub fn new(address: &str) -> Self {
+        Self {
+            version: VERSION,
+            start_time: Utc::now(),
+            address: address.to_owned(),
+            tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+        }
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&self.version.to_be_bytes())?;
+        writer.write_all(&self.start_time.timestamp_micros().to_be_bytes())?;
+        write_string(writer, &self.address)?;
+        write_string(writer, &self.tool_version)?;
+        Ok(())
+    }
+
+    /// Reads everything after the magic
+    fn read(reader: &mut impl Read) -> eyre::Result<Self> {
+        let version = u16::from_be_bytes(read_header_bytes(reader)?);
+        if version > VERSION {
+            eyre::bail!(
+                "Unsupported recording version {version}, the newest supported is {VERSION}"
+            );
+        }
+
+        let start_time = Utc
+            .timestamp_micros(i64::from_be_bytes(read_header_bytes(reader)?))
+            .single()
+            .ok_or_else(|| eyre::eyre!("Recording header has an invalid start time"))?;
+        let address = read_string(reader)?;
+        let tool_version = read_string(reader)?;
+
+        Ok(Self {
+            version,
+            start_time,
+            address,
+            tool_version,
+        })
+    }
+}
+
+/// Reads the frames of a recording one by one, without loading the whole file.
+///
+/// Frames are yielded as the time since the start of the recording and the datagram. A frame
+/// that is cut off, fails its checksum or can't be a packet is yielded as an error, after which
+/// reading stops. [`RecordingScanner`](repair::RecordingScanner) can read past those.
+pub struct RecordingReader {
+    reader: Chain<Cursor<Vec<u8>>, RecordingFile>,
+    header: Option<RecordingHeader>,
+    /// Layout of the frames, legacy recordings use the one of version 1
+    version: u16,
+    /// Offset of the next frame in the (decompressed) recording
+    offset: u64,
+    /// Only frames in these parts of the recording are read, see [`RecordingReader::with_ranges`]
+    ranges: Option<VecDeque<Range<u64>>>,
+    done: bool,
+}
+
+enum RecordingFile {
+    Plain(BufReader<File>),
+    Compressed(zstd::Decoder<'static, BufReader<File>>),
+}
+
+impl RecordingReader {
+    /// Opens a recording, decompressing it if it was compressed, and reads the header. Legacy
+    /// recordings without one are read from the start.
+    pub fn open<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut reader = if file.fill_buf()?.starts_with(&ZSTD_MAGIC) {
+            RecordingFile::Compressed(zstd::Decoder::with_buffer(file)?)
+        } else {
+            RecordingFile::Plain(file)
+        };
+
+        let mut magic = Vec::with_capacity(MAGIC.len());
+        (&mut reader)
+            .take(MAGIC.len() as u64)
+            .read_to_end(&mut magic)?;
+
+        let (header, offset, prefix) = if magic == MAGIC {
+            let mut counting = CountingReader::new(&mut reader);
+            let header = RecordingHeader::read(&mut counting)?;
+            let offset = MAGIC.len() as u64 + counting.count;
+            (Some(header), offset, Vec::new())
+        } else {
+            // Not a header, so these bytes are the start of the first frame
+            (None, 0, magic)
+        };
+
+        Ok(Self {
+            reader: Cursor::new(prefix).chain(reader),
+            version: header.as_ref().map_or(1, |header| header.version),
+            header,
+            offset,
+            ranges: None,
+            done: false,
+        })
+    }
+
+    /// `None` for legacy recordings
+    pub fn header(&self) -> Option<&RecordingHeader> {
+        self.header.as_ref()
+    }
+
+    /// Offset of the next frame in the (decompressed) recording
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Only reads the frames in these parts of the recording, which have to be in order and
+    /// start at a frame (like the ones from [`RecordingIndex::ranges`])
+    pub fn with_ranges(mut self, ranges: Vec<Range<u64>>) -> Self {
+        self.ranges = Some(ranges.into());
+        self
+    }
+
+    /// Skips ahead to the frame at `offset`. Compressed recordings still have to be decompressed
+    /// up to there, but none of the skipped frames are parsed.
+    pub fn seek(&mut self, offset: u64) -> eyre::Result<()> {
+        if offset < self.offset {
+            eyre::bail!("Can only seek forward, from {} to {offset}", self.offset);
+        }
+
+        let mut skip = offset - self.offset;
+        let (prefix, file) = self.reader.get_mut();
+        let from_prefix = skip.min(prefix.get_ref().len() as u64 - prefix.position());
+        prefix.set_position(prefix.position() + from_prefix);
+        skip -= from_prefix;
+
+        match file {
+            RecordingFile::Plain(file) => file.seek_relative(skip as i64)?,
+            RecordingFile::Compressed(decoder) => {
+                io::copy(&mut decoder.take(skip), &mut io::sink())?;
+            }
+        }
+        self.offset = offset;
+        Ok(())
+    }
+
+    /// Moves on to the next range that is read, `false` once they have all been read
+    fn next_range(&mut self) -> eyre::Result<bool> {
+        let Some(ranges) = &mut self.ranges else {
+            return Ok(true);
+        };
+        while ranges.front().is_some_and(|range| self.offset >= range.end) {
+            ranges.pop_front();
+        }
+
+        match ranges.front() {
+            Some(range) if self.offset < range.start => {
+                let start = range.start;
+                self.seek(start)?;
+                Ok(true)
+            }
+            Some(_) => Ok(true),
+            None => Ok(false),
+        }
+    }
+
+    fn read_frame(&mut self) -> eyre::Result<Option<Frame>> {
+        if !self.next_range()? {
+            return Ok(None);
+        }
+
+        let header_size = frame_header_size(self.version);
+        let mut frame_header = [0_u8; FRAME_HEADER_SIZE as usize + 2];
+        let frame_header = &mut frame_header[..header_size as usize];
+        let read = read_up_to(&mut self.reader, frame_header)?;
+        if read == 0 {
+            return Ok(None);
+        } else if read < frame_header.len() {
+            eyre::bail!(
+                "Truncated frame at offset {}, only {read} bytes of its header are left",
+                self.offset
+            );
+        }
+
+        let FrameHeader { size, time, port } = FrameHeader::decode(frame_header)
+            .map_err(|e| eyre::eyre!("Invalid frame at offset {}, {e}", self.offset))?;
+
+        let mut packet = vec![0_u8; size as usize];
+        let read = read_up_to(&mut self.reader, &mut packet)?;
+        if read < packet.len() {
+            eyre::bail!(
+                "Truncated frame at offset {}, only {read} of its {size} bytes are left",
+                self.offset
+            );
+        }
+
+        let trailer_size = frame_trailer_size(self.version);
+        let mut trailer = [0_u8; FRAME_TRAILER_SIZE as usize];
+        let trailer = &mut trailer[..trailer_size as usize];
+        if read_up_to(&mut self.reader, trailer)? < trailer.len() {
+            eyre::bail!(
+                "Truncated frame at offset {}, its checksum is missing",
+                self.offset
+            );
+        }
+        if !check_trailer(frame_header, &packet, trailer) {
+            eyre::bail!(
+                "Corrupt frame at offset {}, its checksum doesn't match (repair can skip past it)",
+                self.offset
+            );
+        }
+
+        self.offset += header_size + size + trailer_size;
+        Ok(Some(Frame {
+            time,
+            port,
+            packet: Bytes::from(packet),
+        }))
+    }
+}
+
+impl Iterator for RecordingReader {
+    type Item = eyre::Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let frame = self.read_frame().transpose();
+        if !matches!(frame, Some(Ok(_))) {
+            self.done = true;
+        }
+        frame
+    }
+}
+
+/// Writes frames to a recording, optionally compressing them with zstd, and keeps its index up
+/// to date if it was created with [`RecordingWriter::create_indexed`].
+///
+/// Compressed recordings are flushed every [`FLUSH_INTERVAL`] of recording time, so everything
+/// up to the last flush can still be read if the writer never gets to [`RecordingWriter::finish`].
+pub struct RecordingWriter {
+    writer: Output,
+    path: PathBuf,
+    /// Layout of the frames, the one of the header's version
+    version: u16,
+    /// Offset of the next frame in the (decompressed) recording
+    offset: u64,
+    index: Option<RecordingIndex>,
+    last_flush: Duration,
+}
+
+enum Output {
+    Plain(File),
+    Compressed(zstd::Encoder<'static, File>),
+}
+
+impl RecordingWriter {
+    /// Creates the recording and writes the header, if there is one
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        header: Option<&RecordingHeader>,
+        compress: bool,
+    ) -> eyre::Result<Self> {
+        Self::create_file(path, header, compress, false)
+    }
+
+    /// Like [`RecordingWriter::create`], but also saves a [`RecordingIndex`] next to the
+    /// recording, which is kept up to date while it is written
+    pub fn create_indexed<P: AsRef<Path>>(
+        path: P,
+        header: Option<&RecordingHeader>,
+        compress: bool,
+    ) -> eyre::Result<Self> {
+        Self::create_file(path, header, compress, true)
+    }
+
+    fn create_file<P: AsRef<Path>>(
+        path: P,
+        header: Option<&RecordingHeader>,
+        compress: bool,
+        indexed: bool,
+    ) -> eyre::Result<Self> {
+        let file = File::create(&path)?;
+        let mut writer = if compress {
+            Output::Compressed(zstd::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)?)
+        } else {
+            Output::Plain(file)
+        };
+
+        let mut header_bytes = Vec::new();
+        if let Some(header) = header {
+            header.write(&mut header_bytes)?;
+        }
+        writer.write_all(&header_bytes)?;
+        writer.flush()?;
+
+        let index = if indexed {
+            let mut index = RecordingIndex::new(header);
+            index.length = header_bytes.len() as u64;
+            index.save(&path)?;
+            Some(index)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            writer,
+            path: path.as_ref().to_owned(),
+            version: header.map_or(1, |header| header.version),
+            offset: header_bytes.len() as u64,
+            index,
+            last_flush: Duration::ZERO,
+        })
+    }
+
+    /// `time` is the time since the start of the recording. The port is left out of recordings
+    /// before version 2.
+    ///
+    /// The frame is put together first and written in one go, so a recorder that is killed
+    /// halfway leaves at most one partial frame, which fails its checksum.
+    pub fn write_frame(
+        &mut self,
+        time: Duration,
+        port: Option<u16>,
+        packet: &Bytes,
+    ) -> eyre::Result<()> {
+        let header_size = frame_header_size(self.version);
+        let mut frame = Vec::with_capacity(
+            (header_size + frame_trailer_size(self.version)) as usize + packet.len(),
+        );
+        frame.extend_from_slice(&(packet.len() as u64).to_be_bytes());
+        frame.extend_from_slice(&time.as_secs_f64().to_be_bytes());
+        if self.version >= 2 {
+            frame.extend_from_slice(&port.unwrap_or(0).to_be_bytes());
+        }
+        frame.extend_from_slice(packet);
+        if self.version >= 3 {
+            let checksum = crc32fast::hash(&frame);
+            frame.extend_from_slice(&(packet.len() as u32).to_be_bytes());
+            frame.extend_from_slice(&checksum.to_be_bytes());
+        }
+        self.writer.write_all(&frame)?;
+
+        let end = self.offset + frame.len() as u64;
+        if let Some(index) = &mut self.index {
+            index.add(self.offset..end, time, packet);
+        }
+        self.offset = end;
+
+        if let Output::Plain(_) = self.writer {
+            // Every frame goes straight to disk
+            self.writer.flush()?;
+        }
+        if time.saturating_sub(self.last_flush) >= FLUSH_INTERVAL {
+            self.flush()?;
+            self.last_flush = time;
+        }
+        Ok(())
+    }
+
+    /// Writes out everything that is buffered and saves the index, without waiting for the
+    /// next [`FLUSH_INTERVAL`]
+    pub fn flush(&mut self) -> eyre::Result<()> {
+        self.writer.flush()?;
+        match &self.index {
+            Some(index) => index.save(&self.path),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes out everything that is still buffered, ends the compressed stream and saves the
+    /// index
+    pub fn finish(self) -> eyre::Result<()> {
+        match self.writer {
+            Output::Plain(mut file) => file.flush()?,
+            Output::Compressed(encoder) => {
+                encoder.finish()?;
+            }
+        }
+        match self.index {
+            Some(mut index) => {
+                index.complete = true;
+                index.save(&self.path)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Read for RecordingFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            RecordingFile::Plain(file) => file.read(buf),
+            RecordingFile::Compressed(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Plain(file) => file.write(buf),
+            Output::Compressed(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(file) => file.flush(),
+            Output::Compressed(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// What is in front of the datagram of a frame
+struct FrameHeader {
+    size: u64,
+    time: Duration,
+    port: Option<u16>,
+}
+
+impl FrameHeader {
+    /// Decodes the header of a frame in the layout of `frame_header_size`, making sure it can
+    /// be the header of a packet
+    fn decode(bytes: &[u8]) -> eyre::Result<Self> {
+        let size = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+        let time = f64::from_be_bytes(bytes[8..16].try_into().unwrap());
+        let port = match bytes[16..] {
+            [high, low] => Some(u16::from_be_bytes([high, low])).filter(|&port| port != 0),
+            _ => None,
+        };
+        if size > MAX_FRAME_SIZE {
+            eyre::bail!("size {size} is too large");
+        }
+        let time = Duration::try_from_secs_f64(time).map_err(|e| eyre::eyre!("bad time: {e}"))?;
+
+        Ok(Self { size, time, port })
+    }
+}
+
+fn frame_header_size(version: u16) -> u64 {
+    if version >= 2 {
+        FRAME_HEADER_SIZE + 2
+    } else {
+        FRAME_HEADER_SIZE
+    }
+}
+
+fn frame_trailer_size(version: u16) -> u64 {
+    if version >= 3 {
+        FRAME_TRAILER_SIZE
+    } else {
+        0
+    }
+}
+
+/// Checks the size and checksum after a packet, recordings before version 3 have none
+fn check_trailer(frame_header: &[u8], packet: &[u8], trailer: &[u8]) -> bool {
+    let [s0, s1, s2, s3, c0, c1, c2, c3] = *trailer else {
+        return trailer.is_empty();
+    };
+
+    // Comparing the sizes is much cheaper, which matters when looking for frames byte by byte
+    if u32::from_be_bytes([s0, s1, s2, s3]) as usize != packet.len() {
+        return false;
+    }
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(frame_header);
+    hasher.update(packet);
+    u32::from_be_bytes([c0, c1, c2, c3]) == hasher.finalize()
+}
+
+/// Keeps track of how many bytes were read, to know where the header ends
+struct CountingReader<R> {
+    reader: R,
+    count: u64,
+}
+
+impl<R> CountingReader<R> {
+    fn new(reader: R) -> Self {
+        Self { reader, count: 0 }
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.count += read as u64;
+        Ok(read)
+    }
+}
+
+/// Like `read_exact`, but returns how much was read when the end of the file is reached.
+///
+/// A compressed recording that was cut off (because the recorder never finished it) ends in the
+/// same way, everything up to the last flush is still read.
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(read)
+}
+
+fn write_string(writer: &mut impl Write, string: &str) -> io::Result<()> {
+    let len = string.len().min(u16::MAX as usize);
+    writer.write_all(&(len as u16).to_be_bytes())?;
+    writer.write_all(&string.as_bytes()[..len])
+}
+
+fn read_header_bytes<const N: usize>(reader: &mut impl Read) -> eyre::Result<[u8; N]> {
+    let mut buf = [0_u8; N];
+    if read_up_to(reader, &mut buf)? < N {
+        eyre::bail!("Recording header is truncated");
+    }
+    Ok(buf)
+}
+
+fn read_string(reader: &mut impl Read) -> eyre::Result<String> {
+    let len = u16::from_be_bytes(read_header_bytes(reader)?) as usize;
+    let mut string = vec![0_u8; len];
+    if read_up_to(reader, &mut string)? < len {
+        eyre::bail!("Recording header is truncated");
+    }
+    Ok(String::from_utf8_lossy(&string).into_owned())
+}
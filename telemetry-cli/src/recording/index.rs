@@ -0,0 +1,402 @@
+use std::{
+    ffi::OsString,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use telemetry::{decode_header, packet::header::PacketId};
+use tracing::warn;
+
+use super::{RecordingHeader, RecordingReader};
+
+const INDEX_VERSION: u16 = 3;
+/// Number of frames at the start of a recording that are hashed to recognise it
+const FINGERPRINT_FRAMES: u32 = 64;
+
+/// Where every session and packet type can be found in a recording, saved next to it as
+/// `<recording>.idx` so tools can skip straight to the frames they need.
+///
+/// Offsets are into the decompressed recording, so a recording and its compressed copy share
+/// the same index.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordingIndex {
+    pub version: u16,
+    /// Start time from the recording header, to notice an index that belongs to another recording
+    pub start_time: Option<DateTime<Utc>>,
+    /// CRC32 of the times and packets of the first frames, legacy recordings have no start time
+    /// so this is the only way to tell them apart
+    pub fingerprint: u32,
+    /// Number of frames in the fingerprint, up to [`FINGERPRINT_FRAMES`]
+    pub fingerprint_frames: u32,
+    /// Offset up to which the recording is indexed, frames after this were written after the
+    /// index was last saved
+    pub length: u64,
+    /// Whether the whole recording is indexed, an index saved while recording is behind as soon
+    /// as the next frame is written
+    pub complete: bool,
+    /// In the order they first appear in the recording
+    pub sessions: Vec<SessionIndex>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionIndex {
+    pub session_uid: u64,
+    pub start_time: Duration,
+    pub end_time: Duration,
+    /// Runs of consecutive frames that all belong to this session
+    pub ranges: Vec<FrameRange>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FrameRange {
+    /// Offset of the first frame
+    pub start: u64,
+    /// Offset right after the last frame
+    pub end: u64,
+    pub start_time: Duration,
+    pub end_time: Duration,
+    pub packets: Vec<PacketRange>,
+}
+
+/// Where the packets of one type are within a [`FrameRange`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PacketRange {
+    pub packet_id: PacketId,
+    /// Runs of consecutive frames with this packet type
+    pub runs: Vec<Range<u64>>,
+    pub count: u32,
+}
+
+impl RecordingIndex {
+    pub fn new(header: Option<&RecordingHeader>) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            start_time: header.map(|header| header.start_time),
+            fingerprint: 0,
+            fingerprint_frames: 0,
+            length: 0,
+            complete: false,
+            sessions: Vec::new(),
+        }
+    }
+
+    /// Indexes a whole recording
+    pub fn build<P: AsRef<Path>>(recording: P) -> eyre::Result<Self> {
+        let mut reader = RecordingReader::open(recording)?;
+        let mut index = Self::new(reader.header());
+
+        loop {
+            let start = reader.offset();
+            match reader.next() {
+                Some(Ok(frame)) => index.add(start..reader.offset(), frame.time, &frame.packet),
+                Some(Err(e)) => {
+                    warn!("Could not read frame: {}", e);
+                    break;
+                }
+                None => break,
+            }
+        }
+        index.complete = true;
+
+        Ok(index)
+    }
+
+    /// `<recording>.idx`
+    pub fn path<P: AsRef<Path>>(recording: P) -> PathBuf {
+        let mut path = OsString::from(recording.as_ref());
+        path.push(".idx");
+        path.into()
+    }
+
+    /// Loads the index of a recording, if there is one and it belongs to the recording
+    pub fn load<P: AsRef<Path>>(
+        recording: P,
+        header: Option<&RecordingHeader>,
+    ) -> eyre::Result<Option<Self>> {
+        let path = Self::path(&recording);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let index: Self = serde_json::from_slice(&std::fs::read(&path)?)?;
+        if index.version != INDEX_VERSION {
+            warn!(
+                ?path,
+                version = index.version,
+                "Ignoring index with unsupported version"
+            );
+            return Ok(None);
+        }
+        if index.start_time != header.map(|header| header.start_time)
+            || index.fingerprint != Self::fingerprint(&recording, index.fingerprint_frames)?
+        {
+            warn!(
+                ?path,
+                "Ignoring index that belongs to a different recording"
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(index))
+    }
+
+    /// Saves the index next to the recording, replacing the old one in one go so a crash never
+    /// leaves half an index behind
+    pub fn save<P: AsRef<Path>>(&self, recording: P) -> eyre::Result<()> {
+        let path = Self::path(recording);
+        let mut temp_path = OsString::from(&path);
+        temp_path.push(".tmp");
+
+        std::fs::write(&temp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+
+    /// Adds a frame by where it is in the recording, frames have to be added in the order they
+    /// were recorded
+    pub fn add(&mut self, offsets: Range<u64>, time: Duration, packet: &Bytes) {
+        let Range { start: offset, end } = offsets;
+        self.length = end;
+        if self.fingerprint_frames < FINGERPRINT_FRAMES {
+            self.fingerprint = add_to_fingerprint(self.fingerprint, time, packet);
+            self.fingerprint_frames += 1;
+        }
+
+        // Frames that can't be parsed are left out, they are read anyway if they are in a range
+        let Ok(header) = decode_header(packet.clone()) else {
+            return;
+        };
+
+        let session = match self
+            .sessions
+            .iter_mut()
+            .position(|session| session.session_uid == header.session_uid)
+        {
+            Some(i) => &mut self.sessions[i],
+            None => {
+                self.sessions.push(SessionIndex {
+                    session_uid: header.session_uid,
+                    start_time: time,
+                    end_time: time,
+                    ranges: Vec::new(),
+                });
+                self.sessions.last_mut().unwrap()
+            }
+        };
+        session.end_time = time;
+
+        let range = match session.ranges.last_mut() {
+            Some(range) if range.end == offset => range,
+            _ => {
+                session.ranges.push(FrameRange {
+                    start: offset,
+                    end: offset,
+                    start_time: time,
+                    end_time: time,
+                    packets: Vec::new(),
+                });
+                session.ranges.last_mut().unwrap()
+            }
+        };
+        range.end = end;
+        range.end_time = time;
+
+        let packets = match range
+            .packets
+            .iter()
+            .position(|packets| packets.packet_id == header.packet_id)
+        {
+            Some(i) => &mut range.packets[i],
+            None => {
+                range.packets.push(PacketRange {
+                    packet_id: header.packet_id,
+                    runs: Vec::new(),
+                    count: 0,
+                });
+                range.packets.last_mut().unwrap()
+            }
+        };
+        match packets.runs.last_mut() {
+            Some(run) if run.end == offset => run.end = end,
+            _ => packets.runs.push(offset..end),
+        }
+        packets.count += 1;
+    }
+
+    /// Hashes the first `frames` frames of a recording like [`RecordingIndex::add`] does
+    fn fingerprint<P: AsRef<Path>>(recording: P, frames: u32) -> eyre::Result<u32> {
+        let mut fingerprint = 0;
+        for frame in RecordingReader::open(recording)?.take(frames as usize) {
+            // A frame that can't be read makes the fingerprint differ, which is what it should do
+            let Ok(frame) = frame else {
+                break;
+            };
+            fingerprint = add_to_fingerprint(fingerprint, frame.time, &frame.packet);
+        }
+        Ok(fingerprint)
+    }
+
+    pub fn session(&self, session_uid: u64) -> Option<&SessionIndex> {
+        self.sessions
+            .iter()
+            .find(|session| session.session_uid == session_uid)
+    }
+
+    /// Parts of the recording that hold the frames of a session (or of every session) with one
+    /// of the packet types (or any type), in order. The part of the recording that isn't indexed
+    /// yet is included unless the index is complete.
+    pub fn ranges(
+        &self,
+        session_uid: Option<u64>,
+        packet_ids: Option<&[PacketId]>,
+    ) -> Vec<Range<u64>> {
+        let mut runs = Vec::new();
+        for range in self
+            .sessions
+            .iter()
+            .filter(|session| session_uid.is_none() || session_uid == Some(session.session_uid))
+            .flat_map(|session| &session.ranges)
+        {
+            match packet_ids {
+                Some(packet_ids) => runs.extend(
+                    range
+                        .packets
+                        .iter()
+                        .filter(|packets| packet_ids.contains(&packets.packet_id))
+                        .flat_map(|packets| packets.runs.iter().cloned()),
+                ),
+                None => runs.push(range.start..range.end),
+            }
+        }
+        runs.sort_by_key(|run| run.start);
+
+        // Runs of different packet types that follow each other are read in one go
+        let mut ranges: Vec<Range<u64>> = Vec::with_capacity(runs.len() + 1);
+        for run in runs {
+            match ranges.last_mut() {
+                Some(range) if range.end == run.start => range.end = run.end,
+                _ => ranges.push(run),
+            }
+        }
+        if !self.complete {
+            ranges.push(self.length..u64::MAX);
+        }
+        ranges
+    }
+}
+
+fn add_to_fingerprint(fingerprint: u32, time: Duration, packet: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new_with_initial(fingerprint);
+    hasher.update(&time.as_secs_f64().to_be_bytes());
+    hasher.update(packet);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packets(packet_id: PacketId, runs: &[(u64, u64)]) -> PacketRange {
+        PacketRange {
+            packet_id,
+            runs: runs.iter().map(|&(start, end)| start..end).collect(),
+            count: runs.len() as u32,
+        }
+    }
+
+    /// Two sessions, the first one's frames are split by a frame of the second
+    fn index(complete: bool) -> RecordingIndex {
+        let range = |start, end, packets| FrameRange {
+            start,
+            end,
+            start_time: Duration::ZERO,
+            end_time: Duration::ZERO,
+            packets,
+        };
+        let session = |session_uid, ranges| SessionIndex {
+            session_uid,
+            start_time: Duration::ZERO,
+            end_time: Duration::ZERO,
+            ranges,
+        };
+        RecordingIndex {
+            version: INDEX_VERSION,
+            start_time: None,
+            fingerprint: 0,
+            fingerprint_frames: 0,
+            length: 700,
+            complete,
+            sessions: vec![
+                session(
+                    1,
+                    vec![
+                        range(
+                            0,
+                            400,
+                            vec![
+                                packets(PacketId::Session, &[(0, 100), (300, 400)]),
+                                packets(PacketId::LapData, &[(100, 200)]),
+                                packets(PacketId::Motion, &[(200, 300)]),
+                            ],
+                        ),
+                        range(500, 700, vec![packets(PacketId::LapData, &[(500, 700)])]),
+                    ],
+                ),
+                session(
+                    2,
+                    vec![range(
+                        400,
+                        500,
+                        vec![packets(PacketId::LapData, &[(400, 500)])],
+                    )],
+                ),
+            ],
+        }
+    }
+
+    fn ranges(
+        index: &RecordingIndex,
+        session_uid: Option<u64>,
+        packet_ids: Option<&[PacketId]>,
+    ) -> Vec<(u64, u64)> {
+        index
+            .ranges(session_uid, packet_ids)
+            .into_iter()
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    #[test]
+    fn ranges_only_hold_the_wanted_frames() {
+        let index = index(true);
+        assert_eq!(ranges(&index, None, None), [(0, 700)]);
+        assert_eq!(ranges(&index, Some(1), None), [(0, 400), (500, 700)]);
+        assert_eq!(
+            ranges(&index, Some(1), Some(&[PacketId::Session])),
+            [(0, 100), (300, 400)]
+        );
+        assert_eq!(
+            ranges(
+                &index,
+                Some(1),
+                Some(&[PacketId::Session, PacketId::LapData])
+            ),
+            [(0, 200), (300, 400), (500, 700)]
+        );
+        assert_eq!(
+            ranges(&index, None, Some(&[PacketId::LapData])),
+            [(100, 200), (400, 700)]
+        );
+    }
+
+    #[test]
+    fn stale_index_reads_the_rest() {
+        let index = index(false);
+        assert_eq!(ranges(&index, Some(2), None), [(400, 500), (700, u64::MAX)]);
+        assert_eq!(ranges(&index, None, None), [(0, 700), (700, u64::MAX)]);
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Read},
+    path::Path,
+    time::Duration,
+};
+
+use bytes::Bytes;
+
+use super::{
+    check_trailer, frame_header_size, frame_trailer_size, Frame, FrameHeader, RecordingFile,
+    RecordingHeader, MAGIC, ZSTD_MAGIC,
+};
+
+/// How much of the recording is read at a time
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Reads every frame that is still intact from a damaged recording, skipping over the parts
+/// that are cut off or corrupt instead of stopping at them like
+/// [`RecordingReader`](super::RecordingReader).
+///
+/// The recording is read as a stream, only the part that is being scanned is kept in memory,
+/// which is never much more than the largest possible frame. After a damaged part the scanner
+/// looks for the next intact frame byte by byte. Since version 3 a frame is only accepted if its
+/// checksum matches, the checksum is only calculated once the size after the packet matches
+/// the one in front of it. Older recordings have none, so there the frame after a damaged part
+/// also can't be from before the previous one and has to be followed by another frame.
+pub struct RecordingScanner {
+    reader: RecordingFile,
+    header: Option<RecordingHeader>,
+    version: u16,
+    compressed: bool,
+    /// Read from the recording but not scanned yet, starting at `position`
+    window: Vec<u8>,
+    position: usize,
+    /// Offset of the byte at `position` in the (decompressed) recording
+    offset: u64,
+    /// Whether everything that could be read from the recording is in the window
+    ended: bool,
+    /// Why the rest of the recording couldn't be read
+    stream_error: Option<String>,
+    /// Time of the last intact frame
+    last_time: Option<Duration>,
+}
+
+/// A part of a recording that was skipped
+#[derive(Clone, Debug)]
+pub struct Damage {
+    /// Offset of the first skipped byte in the (decompressed) recording
+    pub start: u64,
+    /// Offset of the next intact frame, or the end of the recording
+    pub end: u64,
+    pub reason: String,
+    /// Time of the intact frame before the damaged part
+    pub previous: Option<Duration>,
+    /// Time of the intact frame after the damaged part
+    pub next: Option<Duration>,
+}
+
+impl RecordingScanner {
+    /// Opens a recording and reads its header. Only fails if the file can't be opened or its
+    /// header is damaged.
+    pub fn open<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+        let compressed = file.fill_buf()?.starts_with(&ZSTD_MAGIC);
+        let reader = if compressed {
+            RecordingFile::Compressed(zstd::Decoder::with_buffer(file)?)
+        } else {
+            RecordingFile::Plain(file)
+        };
+
+        let mut scanner = Self {
+            reader,
+            header: None,
+            version: 1,
+            compressed,
+            window: Vec::new(),
+            position: 0,
+            offset: 0,
+            ended: false,
+            stream_error: None,
+            last_time: None,
+        };
+
+        // The header is only a few bytes, so it's in the first chunk
+        scanner.fill(CHUNK_SIZE);
+        if scanner.window.starts_with(MAGIC) {
+            let mut cursor = Cursor::new(&scanner.window[MAGIC.len()..]);
+            let header = RecordingHeader::read(&mut cursor)?;
+            let size = MAGIC.len() + cursor.position() as usize;
+            scanner.version = header.version;
+            scanner.header = Some(header);
+            scanner.advance(size);
+        }
+
+        Ok(scanner)
+    }
+
+    /// `None` for legacy recordings
+    pub fn header(&self) -> Option<&RecordingHeader> {
+        self.header.as_ref()
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// Why the end of the recording couldn't be read (mostly because a compressed recording was
+    /// cut off), everything after that is lost
+    pub fn stream_error(&self) -> Option<&str> {
+        self.stream_error.as_deref()
+    }
+
+    /// Offset of the next frame in the (decompressed) recording, which is the size of the
+    /// recording once every frame was read
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Reads until there are `len` bytes after the position in the window, or the recording
+    /// ends. Returns how many of those bytes there are.
+    fn fill(&mut self, len: usize) -> usize {
+        while self.window.len() - self.position < len && !self.ended {
+            // Drop what was already scanned first, so the window doesn't keep growing
+            self.window.drain(..self.position);
+            self.position = 0;
+
+            let filled = self.window.len();
+            self.window.resize(filled + CHUNK_SIZE, 0);
+            let read = match self.reader.read(&mut self.window[filled..]) {
+                Ok(read) => {
+                    self.ended = read == 0;
+                    read
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => 0,
+                Err(e) => {
+                    self.stream_error = Some(e.to_string());
+                    self.ended = true;
+                    0
+                }
+            };
+            self.window.truncate(filled + read);
+        }
+
+        (self.window.len() - self.position).min(len)
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.position += len;
+        self.offset += len as u64;
+    }
+
+    /// Decodes the frame at the position and its size in the recording, if it is intact
+    fn frame(&mut self) -> eyre::Result<(Frame, usize)> {
+        let header_size = frame_header_size(self.version) as usize;
+        let trailer_size = frame_trailer_size(self.version) as usize;
+        let available = self.fill(header_size);
+        if available < header_size {
+            eyre::bail!("only {available} bytes of its header are left");
+        }
+
+        let data = &self.window[self.position..];
+        let FrameHeader { size, time, port } = FrameHeader::decode(&data[..header_size])?;
+        let packet_end = header_size + size as usize;
+        let frame_size = packet_end + trailer_size;
+        if self.fill(frame_size) < frame_size {
+            eyre::bail!(
+                "only {} of its {size} bytes are left",
+                self.window.len() - self.position - header_size
+            );
+        }
+
+        let data = &self.window[self.position..];
+        let packet = &data[header_size..packet_end];
+        if !check_trailer(&data[..header_size], packet, &data[packet_end..frame_size]) {
+            eyre::bail!("its checksum doesn't match");
+        }
+
+        Ok((
+            Frame {
+                time,
+                port,
+                packet: Bytes::copy_from_slice(packet),
+            },
+            frame_size,
+        ))
+    }
+
+    /// Whether a frame found while looking past a damaged part really is one, and not just bytes
+    /// that happen to look like a frame header
+    fn is_resync(&mut self, frame: &Frame, size: usize) -> bool {
+        if frame_trailer_size(self.version) > 0 {
+            // The checksum already matched
+            return true;
+        }
+
+        let in_order = match self.last_time {
+            Some(last_time) => frame.time >= last_time,
+            None => true,
+        };
+        if !in_order {
+            return false;
+        }
+        let header_size = frame_header_size(self.version) as usize;
+        let next = self.position + size;
+        self.fill(size + header_size) < size + header_size
+            || FrameHeader::decode(&self.window[next..next + header_size]).is_ok()
+    }
+}
+
+impl Iterator for RecordingScanner {
+    type Item = Result<Frame, Damage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fill(1) == 0 {
+            return None;
+        }
+
+        let reason = match self.frame() {
+            Ok((frame, size)) => {
+                self.advance(size);
+                self.last_time = Some(frame.time);
+                return Some(Ok(frame));
+            }
+            Err(e) => e.to_string(),
+        };
+
+        // Look for the next frame that is intact, byte by byte
+        let start = self.offset;
+        let next = loop {
+            self.advance(1);
+            if self.fill(1) == 0 {
+                break None;
+            }
+            if let Ok((frame, size)) = self.frame() {
+                if self.is_resync(&frame, size) {
+                    break Some(frame.time);
+                }
+            }
+        };
+
+        Some(Err(Damage {
+            start,
+            end: self.offset,
+            reason,
+            previous: self.last_time,
+            next,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::test_util::TempDir;
+
+    const ADDRESS: &str = "0.0.0.0:20777";
+
+    /// A recording in a directory of its own, which is removed again afterwards
+    struct TestRecording {
+        _dir: TempDir,
+        path: PathBuf,
+        /// Where the frames start
+        frames_start: usize,
+        frame_size: usize,
+    }
+
+    impl TestRecording {
+        /// Writes `frames` packets of `size` bytes in the layout of `version`, version 1 is a
+        /// legacy recording without a header. Every packet starts with 0xff and its number.
+        fn new(name: &str, version: u16, frames: u16, size: usize) -> Self {
+            let dir = TempDir::new(&format!("repair-{name}"));
+            let header = (version > 1).then(|| RecordingHeader {
+                version,
+                ..RecordingHeader::new(ADDRESS)
+            });
+            let path = dir.recording(
+                "recording.bin",
+                header.as_ref(),
+                (0..frames).map(|i| (Self::time(i), Self::packet(i, size))),
+            );
+
+            let mut frames_start = Vec::new();
+            if let Some(header) = &header {
+                header.write(&mut frames_start).unwrap();
+            }
+            Self {
+                _dir: dir,
+                path,
+                frames_start: frames_start.len(),
+                frame_size: (frame_header_size(version) + frame_trailer_size(version)) as usize
+                    + size,
+            }
+        }
+
+        fn time(i: u16) -> Duration {
+            Duration::from_millis(10 * (i as u64 + 1))
+        }
+
+        fn packet(i: u16, size: usize) -> Bytes {
+            let mut packet = vec![0xff; size];
+            packet[1..3].copy_from_slice(&i.to_be_bytes());
+            packet.into()
+        }
+
+        /// Offset of a frame in the recording
+        fn offset(&self, i: u16) -> usize {
+            self.frames_start + i as usize * self.frame_size
+        }
+
+        fn change(&self, change: impl FnOnce(&mut Vec<u8>)) {
+            let mut bytes = std::fs::read(&self.path).unwrap();
+            change(&mut bytes);
+            std::fs::write(&self.path, bytes).unwrap();
+        }
+
+        /// The numbers of the frames that were kept and the damaged parts
+        fn scan(&self) -> (Vec<u16>, Vec<Damage>) {
+            let mut scanner = RecordingScanner::open(&self.path).unwrap();
+            let mut frames = Vec::new();
+            let mut damage = Vec::new();
+            for frame in scanner.by_ref() {
+                match frame {
+                    Ok(frame) => {
+                        let i = u16::from_be_bytes([frame.packet[1], frame.packet[2]]);
+                        assert_eq!(frame.time, Self::time(i));
+                        assert_eq!(frame.packet, Self::packet(i, frame.packet.len()));
+                        frames.push(i);
+                    }
+                    Err(e) => damage.push(e),
+                }
+            }
+            assert!(scanner.stream_error().is_none());
+            assert_eq!(
+                scanner.offset(),
+                std::fs::metadata(&self.path).unwrap().len()
+            );
+            (frames, damage)
+        }
+    }
+
+    fn all_but(frames: u16, skipped: &[u16]) -> Vec<u16> {
+        (0..frames).filter(|i| !skipped.contains(i)).collect()
+    }
+
+    #[test]
+    fn intact_recording() {
+        let recording = TestRecording::new("intact", 3, 10, 100);
+        let (frames, damage) = recording.scan();
+        assert_eq!(frames, all_but(10, &[]));
+        assert!(damage.is_empty());
+    }
+
+    #[test]
+    fn truncated_recording() {
+        let recording = TestRecording::new("truncated", 3, 10, 100);
+        recording.change(|bytes| bytes.truncate(bytes.len() - 30));
+
+        let (frames, damage) = recording.scan();
+        assert_eq!(frames, all_but(10, &[9]));
+        let [damage] = damage.as_slice() else {
+            panic!("expected one damaged part, got {damage:?}");
+        };
+        assert_eq!(damage.start, recording.offset(9) as u64);
+        assert_eq!(damage.end, recording.offset(10) as u64 - 30);
+        assert_eq!(damage.previous, Some(TestRecording::time(8)));
+        assert_eq!(damage.next, None);
+    }
+
+    #[test]
+    fn bit_flips() {
+        let recording = TestRecording::new("bit_flips", 3, 10, 100);
+        recording.change(|bytes| {
+            // In a packet
+            bytes[recording.offset(4) + 60] ^= 0x10;
+            // In the size in front of a packet
+            bytes[recording.offset(7) + 7] ^= 0x01;
+        });
+
+        let (frames, damage) = recording.scan();
+        assert_eq!(frames, all_but(10, &[4, 7]));
+        let [packet, size] = damage.as_slice() else {
+            panic!("expected two damaged parts, got {damage:?}");
+        };
+        assert_eq!(packet.start, recording.offset(4) as u64);
+        assert_eq!(packet.end, recording.offset(5) as u64);
+        assert_eq!(packet.previous, Some(TestRecording::time(3)));
+        assert_eq!(packet.next, Some(TestRecording::time(5)));
+        assert_eq!(size.start, recording.offset(7) as u64);
+        assert_eq!(size.end, recording.offset(8) as u64);
+    }
+
+    #[test]
+    fn larger_than_the_window() {
+        let recording = TestRecording::new("large", 3, 300, 1400);
+        assert!(recording.offset(300) > 4 * CHUNK_SIZE);
+        recording.change(|bytes| bytes[recording.offset(150) + 1000] ^= 0x01);
+
+        let (frames, damage) = recording.scan();
+        assert_eq!(frames, all_but(300, &[150]));
+        assert_eq!(damage.len(), 1);
+    }
+
+    #[test]
+    fn legacy_damaged_size() {
+        let recording = TestRecording::new("legacy_v2", 2, 10, 100);
+        recording.change(|bytes| {
+            let size = recording.offset(3);
+            bytes[size + 4..size + 8].fill(0xff);
+        });
+
+        let (frames, damage) = recording.scan();
+        assert_eq!(frames, all_but(10, &[3]));
+        let [damage] = damage.as_slice() else {
+            panic!("expected one damaged part, got {damage:?}");
+        };
+        assert_eq!(damage.start, recording.offset(3) as u64);
+        assert_eq!(damage.end, recording.offset(4) as u64);
+        assert!(damage.reason.contains("too large"), "{}", damage.reason);
+    }
+
+    #[test]
+    fn legacy_without_header_truncated() {
+        let recording = TestRecording::new("legacy_v1", 1, 10, 100);
+        recording.change(|bytes| bytes.truncate(recording.offset(9) + 10));
+
+        let (frames, damage) = recording.scan();
+        assert_eq!(frames, all_but(10, &[9]));
+        let [damage] = damage.as_slice() else {
+            panic!("expected one damaged part, got {damage:?}");
+        };
+        assert_eq!(damage.start, recording.offset(9) as u64);
+        assert!(damage.reason.contains("header"), "{}", damage.reason);
+    }
+}
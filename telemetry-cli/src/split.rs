@@ -0,0 +1,341 @@
+use std::{path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Local, Utc};
+use telemetry::{
+    decode_header, decode_packet,
+    packet::{
+        event::Event,
+        header::PacketId,
+        session::{SessionType, Track},
+        Packet,
+    },
+};
+use tracing::{debug, warn};
+
+use crate::recording::{Frame, RecordingHeader, RecordingWriter};
+
+/// How long to wait for a session packet to name a new file, the game sends them twice a second
+const PENDING_LIMIT: Duration = Duration::from_secs(5);
+
+/// Writes every session to its own recording, named after a template.
+///
+/// A new recording is started when the session UID changes, or when a session started event
+/// follows a session ended event (a restarted session can keep its UID). Menu traffic (session
+/// UID 0) is dropped. The template can contain `{date}`, `{time}`, `{track}` (the track's name),
+/// `{session_type}` and `{session_uid}`, so it isn't known where a session goes until its first
+/// session packet arrives; everything before that is held back.
+pub struct SessionSplitter {
+    template: String,
+    /// Header of every recording, with its start time moved to the first frame of the session
+    header: RecordingHeader,
+    compress: bool,
+    state: State,
+}
+
+enum State {
+    Idle,
+    /// Waiting for the session packet
+    Pending {
+        session_uid: u64,
+        frames: Vec<Frame>,
+    },
+    Writing {
+        session_uid: u64,
+        writer: RecordingWriter,
+        /// Recording time of the first frame, frame times are relative to this
+        start: Duration,
+        /// A session ended event arrived, so a session started event begins a new recording
+        ended: bool,
+    },
+}
+
+impl SessionSplitter {
+    /// `header` is the one of the whole recording, frame times are relative to its start time
+    pub fn new(template: &str, header: RecordingHeader, compress: bool) -> Self {
+        Self {
+            template: template.to_owned(),
+            header,
+            compress,
+            state: State::Idle,
+        }
+    }
+
+    pub fn write_frame(&mut self, frame: Frame) -> eyre::Result<()> {
+        let header = match decode_header(frame.packet.clone()) {
+            Ok(header) => header,
+            Err(_) => {
+                // Can't tell which session it belongs to, so keep it with the current one
+                match &mut self.state {
+                    State::Idle => {}
+                    State::Pending { frames, .. } => frames.push(frame),
+                    State::Writing { writer, start, .. } => writer.write_frame(
+                        frame.time.saturating_sub(*start),
+                        frame.port,
+                        &frame.packet,
+                    )?,
+                }
+                return Ok(());
+            }
+        };
+        if header.session_uid == 0 {
+            return Ok(());
+        }
+
+        let event = match header.packet_id {
+            PacketId::Event => match decode_packet(frame.packet.clone()) {
+                Ok(Packet::Event(event)) => Some(event.event),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match &mut self.state {
+            State::Writing {
+                session_uid, ended, ..
+            } if *session_uid == header.session_uid
+                && !(*ended && matches!(event, Some(Event::SessionStarted))) =>
+            {
+                if matches!(event, Some(Event::SessionEnded)) {
+                    *ended = true;
+                }
+            }
+            State::Pending { session_uid, .. } if *session_uid == header.session_uid => {}
+            _ => {
+                self.finish_session()?;
+                debug!(session_uid = header.session_uid, "New session");
+                self.state = State::Pending {
+                    session_uid: header.session_uid,
+                    frames: Vec::new(),
+                };
+            }
+        }
+
+        match &mut self.state {
+            State::Idle => {}
+            State::Pending { frames, .. } => {
+                let session = match decode_packet(frame.packet.clone()) {
+                    Ok(Packet::Session(session)) => Some((session.track, session.session_type)),
+                    _ => None,
+                };
+                frames.push(frame);
+
+                let waited = frames.last().unwrap().time.saturating_sub(frames[0].time);
+                if session.is_some() || waited > PENDING_LIMIT {
+                    let (track, session_type) =
+                        session.unwrap_or((Track::Unknown, SessionType::Unknown));
+                    self.start_session(header.session_uid, track, session_type)?;
+                }
+            }
+            State::Writing { writer, start, .. } => {
+                writer.write_frame(frame.time.saturating_sub(*start), frame.port, &frame.packet)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> eyre::Result<()> {
+        match &mut self.state {
+            State::Writing { writer, .. } => writer.flush(),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn finish(mut self) -> eyre::Result<()> {
+        self.finish_session()
+    }
+
+    fn finish_session(&mut self) -> eyre::Result<()> {
+        match std::mem::replace(&mut self.state, State::Idle) {
+            State::Writing { writer, .. } => writer.finish(),
+            State::Pending {
+                session_uid,
+                frames,
+            } if !frames.is_empty() => {
+                // Never got to know the session, still keep what was received
+                self.state = State::Pending {
+                    session_uid,
+                    frames,
+                };
+                self.start_session(session_uid, Track::Unknown, SessionType::Unknown)?;
+                self.finish_session()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Opens the recording of the pending session and writes the frames that were held back
+    fn start_session(
+        &mut self,
+        session_uid: u64,
+        track: Track,
+        session_type: SessionType,
+    ) -> eyre::Result<()> {
+        let State::Pending { frames, .. } = std::mem::replace(&mut self.state, State::Idle) else {
+            return Ok(());
+        };
+        let start = frames.first().map_or(Duration::ZERO, |frame| frame.time);
+
+        let mut header = self.header.clone();
+        header.start_time += chrono::Duration::from_std(start)?;
+        let path = self.path(header.start_time, session_uid, track, session_type);
+        println!(
+            "Recording session {} to {}",
+            session_uid,
+            path.to_string_lossy()
+        );
+
+        let mut writer = RecordingWriter::create_indexed(&path, Some(&header), self.compress)?;
+        for frame in frames {
+            writer.write_frame(frame.time.saturating_sub(start), frame.port, &frame.packet)?;
+        }
+
+        self.state = State::Writing {
+            session_uid,
+            writer,
+            start,
+            ended: false,
+        };
+        Ok(())
+    }
+
+    /// Fills in the template, numbering the file if a session with the same name was recorded
+    fn path(
+        &self,
+        start_time: DateTime<Utc>,
+        session_uid: u64,
+        track: Track,
+        session_type: SessionType,
+    ) -> PathBuf {
+        let start_time = start_time.with_timezone(&Local);
+        // Only keep characters that are safe in file names on every platform
+        let fill = |value: String| value.replace(|c: char| !c.is_alphanumeric() && c != '-', "_");
+        let path = PathBuf::from(
+            self.template
+                .replace("{date}", &fill(start_time.format("%Y-%m-%d").to_string()))
+                .replace("{time}", &fill(start_time.format("%H-%M-%S").to_string()))
+                .replace("{track}", &fill(track.name().to_owned()))
+                .replace("{session_type}", &fill(format!("{:?}", session_type)))
+                .replace("{session_uid}", &session_uid.to_string()),
+        );
+
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = path
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+        let mut numbered = path.clone();
+        for i in 2.. {
+            if !numbered.exists() {
+                break;
+            }
+            numbered.set_file_name(format!("{stem}_{i}{extension}"));
+        }
+        if numbered != path {
+            debug!(?path, "A recording with this name already exists");
+        }
+        numbered
+    }
+}
+
+impl Drop for SessionSplitter {
+    /// Writes out the frames that are still held back if the splitter isn't finished, like when
+    /// recording stops with an error
+    fn drop(&mut self) {
+        if let Err(e) = self.finish_session() {
+            warn!("Could not finish the recording of the last session: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use telemetry::test_util::packet;
+
+    use super::*;
+    use crate::{recording::RecordingReader, test_util::TempDir};
+
+    const SESSION: u8 = 1;
+    const EVENT: u8 = 3;
+    const CAR_STATUS: u8 = 7;
+
+    fn frame(frame: u32, packet: bytes::BytesMut) -> Frame {
+        Frame {
+            time: Duration::from_millis(10 * frame as u64),
+            port: Some(20777),
+            packet: packet.freeze(),
+        }
+    }
+
+    fn session(session_uid: u64, i: u32) -> Frame {
+        frame(i, packet(2023, SESSION, session_uid, i, 0, 644))
+    }
+
+    fn event(session_uid: u64, i: u32, code: &[u8; 4]) -> Frame {
+        let mut packet = packet(2023, EVENT, session_uid, i, 0, 45);
+        packet[29..33].copy_from_slice(code);
+        frame(i, packet)
+    }
+
+    fn car_status(session_uid: u64, i: u32) -> Frame {
+        frame(i, packet(2023, CAR_STATUS, session_uid, i, 0, 1239))
+    }
+
+    fn splitter(dir: &TempDir) -> SessionSplitter {
+        let template = dir.path().join("{session_uid}.bin");
+        SessionSplitter::new(
+            &template.to_string_lossy(),
+            RecordingHeader::new("0.0.0.0:20777"),
+            false,
+        )
+    }
+
+    /// Frame identifiers of the frames in a recording
+    fn frames(path: &Path) -> Vec<u32> {
+        RecordingReader::open(path)
+            .unwrap()
+            .map(|frame| {
+                decode_header(frame.unwrap().packet)
+                    .unwrap()
+                    .frame_identifier
+            })
+            .collect()
+    }
+
+    #[test]
+    fn restarted_sessions_get_their_own_recording() {
+        let dir = TempDir::new("split-restart");
+        let mut splitter = splitter(&dir);
+        for frame in [
+            event(1, 0, b"SSTA"),
+            session(1, 1),
+            // The game sends it again, that doesn't start a new session
+            event(1, 2, b"SSTA"),
+            car_status(1, 3),
+            event(1, 4, b"SEND"),
+            event(1, 5, b"SSTA"),
+            session(1, 6),
+            session(2, 7),
+        ] {
+            splitter.write_frame(frame).unwrap();
+        }
+        splitter.finish().unwrap();
+
+        assert_eq!(frames(&dir.path().join("1.bin")), [0, 1, 2, 3, 4]);
+        assert_eq!(frames(&dir.path().join("1_2.bin")), [5, 6]);
+        assert_eq!(frames(&dir.path().join("2.bin")), [7]);
+    }
+
+    #[test]
+    fn held_back_frames_are_written_when_dropped() {
+        let dir = TempDir::new("split-drop");
+        let mut splitter = splitter(&dir);
+        splitter.write_frame(car_status(1, 0)).unwrap();
+        splitter.write_frame(car_status(1, 1)).unwrap();
+        drop(splitter);
+
+        assert_eq!(frames(&dir.path().join("1.bin")), [0, 1]);
+    }
+}
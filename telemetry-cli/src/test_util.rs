@@ -0,0 +1,48 @@
+//! Helpers shared by the tests
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bytes::Bytes;
+
+use crate::recording::{RecordingHeader, RecordingWriter};
+
+/// A directory of its own for the files of a test, which is removed again afterwards
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` has to be unique among the tests, they run in parallel
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("telemetry-cli-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Writes an uncompressed recording, without a header if there is none
+    pub fn recording(
+        &self,
+        name: &str,
+        header: Option<&RecordingHeader>,
+        frames: impl IntoIterator<Item = (Duration, Bytes)>,
+    ) -> PathBuf {
+        let path = self.0.join(name);
+        let mut writer = RecordingWriter::create(&path, header, false).unwrap();
+        for (time, packet) in frames {
+            writer.write_frame(time, Some(20777), &packet).unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,10 @@
+#![no_main]
+
+use bytes::Bytes;
+use libfuzzer_sys::fuzz_target;
+
+// Decoding arbitrary datagrams must never panic, only return errors
+fuzz_target!(|data: &[u8]| {
+    let _ = telemetry::decode_header(Bytes::copy_from_slice(data));
+    let _ = telemetry::decode_packet(Bytes::copy_from_slice(data));
+});
@@ -0,0 +1,21 @@
+use std::io::Cursor;
+
+use bytes::Bytes;
+
+use crate::{f1_23::car_damage::parse_car_damage_data, packet::car_damage::CarDamagePacket};
+
+use super::header::parse_header;
+
+pub fn parse_car_damage_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<CarDamagePacket> {
+    crate::check_packet_length(cursor, "car damage", 948)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_damage_data = (0..22).map(|_| parse_car_damage_data(cursor)).collect();
+
+    Ok(CarDamagePacket {
+        header,
+        raw: None,
+        car_damage_data,
+    })
+}
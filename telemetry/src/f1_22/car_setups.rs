@@ -0,0 +1,22 @@
+use std::io::Cursor;
+
+use bytes::Bytes;
+
+use crate::{f1_23::car_setups::parse_car_setup_data, packet::car_setups::CarSetupsPacket};
+
+use super::header::parse_header;
+
+pub fn parse_car_setups_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<CarSetupsPacket> {
+    crate::check_packet_length(cursor, "car setups", 1102)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_setups = (0..22).map(|_| parse_car_setup_data(cursor)).collect();
+
+    Ok(CarSetupsPacket {
+        header,
+        raw: None,
+        car_setups,
+        next_front_wing_value: None,
+    })
+}
@@ -0,0 +1,32 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::car_telemetry::{parse_car_telemetry_data, parse_mfd_panel_index},
+    packet::car_telemetry::CarTelemetryPacket,
+};
+
+use super::header::parse_header;
+
+pub fn parse_car_telemetry(cursor: &mut Cursor<Bytes>) -> crate::Result<CarTelemetryPacket> {
+    crate::check_packet_length(cursor, "car telemetry", 1347)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_telemetry_data: Vec<_> = (0..22).map(|_| parse_car_telemetry_data(cursor)).collect();
+
+    let mfd_panel_index = parse_mfd_panel_index(cursor);
+    let mfd_panel_index_secondary_player = parse_mfd_panel_index(cursor);
+
+    let suggested_gear = cursor.get_i8();
+
+    Ok(CarTelemetryPacket {
+        header,
+        raw: None,
+        car_telemetry_data,
+        mfd_panel_index,
+        mfd_panel_index_secondary_player,
+        suggested_gear,
+    })
+}
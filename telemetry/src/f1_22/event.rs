@@ -0,0 +1,19 @@
+use bytes::Bytes;
+use std::io::Cursor;
+
+use crate::{f1_23::event::parse_event, packet::event::EventPacket};
+
+use super::header::parse_header;
+
+pub fn parse_event_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<EventPacket> {
+    crate::check_packet_length(cursor, "event", 40)?;
+
+    let header = parse_header(cursor)?;
+    let event = parse_event(cursor)?;
+
+    Ok(EventPacket {
+        header,
+        event,
+        raw: None,
+    })
+}
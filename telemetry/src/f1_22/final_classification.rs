@@ -0,0 +1,37 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::final_classification::parse_final_classification_data,
+    packet::final_classification::FinalClassificationPacket, Result,
+};
+
+use super::header::parse_header;
+
+pub fn parse_final_classification_packet(
+    cursor: &mut Cursor<Bytes>,
+) -> Result<FinalClassificationPacket> {
+    crate::check_packet_length(cursor, "final classification", 1015)?;
+
+    let header = parse_header(cursor)?;
+    let _num_cars = cursor.get_u8();
+    let classification_data = (0..22)
+        .map(|_| {
+            let classification_data = parse_final_classification_data(cursor);
+            classification_data.map(|cd| {
+                if cd.status.valid_result() {
+                    Some(cd)
+                } else {
+                    None
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(FinalClassificationPacket {
+        header,
+        raw: None,
+        classification_data,
+    })
+}
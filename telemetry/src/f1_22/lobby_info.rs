@@ -0,0 +1,59 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::participants::{parse_name, parse_team},
+    packet::lobby_info::{LobbyInfoData, LobbyInfoPacket, Status},
+    Result,
+};
+
+use super::header::parse_header;
+
+pub fn parse_lobby_info(cursor: &mut Cursor<Bytes>) -> Result<LobbyInfoPacket> {
+    crate::check_packet_length(cursor, "lobby info", 1191)?;
+
+    let header = parse_header(cursor)?;
+    let num_players = cursor.get_u8();
+    if num_players > 22 {
+        return Err(crate::TelemetryError::InvalidPacket(format!(
+            "invalid number of players in lobby info packet: {num_players}"
+        )));
+    }
+    let lobby_players = (0..num_players)
+        .map(|_| parse_lobby_info_data(cursor))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(LobbyInfoPacket {
+        header,
+        raw: None,
+        lobby_players,
+    })
+}
+
+fn parse_lobby_info_data(cursor: &mut Cursor<Bytes>) -> Result<LobbyInfoData> {
+    let ai_controlled: bool = cursor.get_u8() != 0;
+    let team = parse_team(cursor.get_u8());
+    let nationality = cursor.get_u8();
+    let name = parse_name(cursor);
+    let car_number = cursor.get_u8();
+    let ready_status = match cursor.get_u8() {
+        0 => Status::NotReady,
+        1 => Status::Ready,
+        2 => Status::Spectating,
+        _ => return Err(crate::TelemetryError::InvalidPacket("invalid ready_status".to_owned())),
+    };
+
+    Ok(LobbyInfoData {
+        ai_controlled,
+        team,
+        nationality,
+        platform: None,
+        name,
+        car_number,
+        your_telemetry: None,
+        show_online_names: None,
+        tech_level: None,
+        ready_status,
+    })
+}
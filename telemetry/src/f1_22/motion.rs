@@ -0,0 +1,20 @@
+use crate::{f1_23::motion::parse_car_motion_data, packet::motion::MotionPacket};
+use bytes::Bytes;
+use std::io::Cursor;
+
+use super::header::parse_header;
+
+pub fn parse_motion_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<MotionPacket> {
+    crate::check_packet_length(cursor, "motion", 1464)?;
+
+    let header = parse_header(cursor)?;
+
+    // The extra player car data at the end of the packet is skipped
+    let car_motion_data = (0..22).map(|_| parse_car_motion_data(cursor)).collect();
+
+    Ok(MotionPacket {
+        header,
+        raw: None,
+        car_motion_data,
+    })
+}
@@ -0,0 +1,56 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::participants::{parse_name, parse_nationality, parse_team},
+    packet::participants::{ParticipantData, ParticipantsPacket, Telemetry},
+};
+
+use super::header::parse_header;
+
+pub fn parse_participants_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<ParticipantsPacket> {
+    crate::check_packet_length(cursor, "participants", 1257)?;
+    let header = parse_header(cursor)?;
+
+    let num_active_cars = cursor.get_u8();
+    let participants = (0..22).map(|_| parse_participants_data(cursor)).collect();
+
+    Ok(ParticipantsPacket {
+        header,
+        raw: None,
+        num_active_cars,
+        participants,
+    })
+}
+
+fn parse_participants_data(cursor: &mut Cursor<Bytes>) -> ParticipantData {
+    let ai_controlled = cursor.get_u8() != 0;
+    let driver_id = cursor.get_u8();
+    let network_id = cursor.get_u8();
+    let team = parse_team(cursor.get_u8());
+    let my_team = cursor.get_u8() != 0;
+    let race_number = cursor.get_u8();
+    let nationality = parse_nationality(cursor.get_u8());
+    let name = parse_name(cursor);
+    let your_telemetry = if cursor.get_u8() == 1 {
+        Telemetry::Public
+    } else {
+        Telemetry::Restricted
+    };
+
+    ParticipantData {
+        ai_controlled,
+        driver_id,
+        network_id,
+        team,
+        my_team,
+        race_number,
+        nationality,
+        name,
+        your_telemetry,
+        show_online_names: None,
+        tech_level: None,
+        platform: None,
+    }
+}
@@ -0,0 +1,163 @@
+use bytes::{Buf, Bytes};
+
+use std::io::Cursor;
+
+use crate::{
+    f1_23::session::{
+        parse_game_mode, parse_marshal_zone, parse_ruleset, parse_session_length,
+        parse_session_type, parse_track, parse_weather, parse_weather_forecast_sample,
+    },
+    packet::session::{
+        BrakingAssist, DynamicRacingLine, DynamicRacingLineType, ForecastAccuracy, Formula,
+        GearboxAssist, MarshalZone, SafetyCarStatus, SessionPacket, WeatherForecastSample,
+    },
+};
+
+use super::header::parse_header;
+
+pub fn parse_session_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<SessionPacket> {
+    crate::check_packet_length(cursor, "session", 632)?;
+
+    let header = parse_header(cursor)?;
+
+    let weather = parse_weather(cursor);
+    let track_temperature = cursor.get_i8();
+    let air_temperature = cursor.get_i8();
+    let total_laps = cursor.get_u8();
+    let track_length = cursor.get_u16_le();
+    let session_type = parse_session_type(cursor);
+    let track = parse_track(cursor);
+    let formula = match cursor.get_u8() {
+        1 => Formula::F1Classic,
+        2 => Formula::F2,
+        3 => Formula::F1Generic,
+        4 => Formula::Beta,
+        5 => Formula::Supercars,
+        6 => Formula::Esports,
+        7 => Formula::F2_2021,
+        _ => Formula::F1Modern,
+    };
+    let session_time_left = cursor.get_u16_le();
+    let session_duration = cursor.get_u16_le();
+    let pit_speed_limit = cursor.get_u8();
+    let game_paused = cursor.get_u8() != 0;
+    let is_spectating = cursor.get_u8() != 0;
+    let spectator_car_index = cursor.get_u8();
+    let sli_pro_native_support = cursor.get_u8() != 0;
+    let num_marshal_zones = cursor.get_u8();
+    let mut marshal_zones: Vec<MarshalZone> = (0..21).map(|_| parse_marshal_zone(cursor)).collect();
+    marshal_zones.truncate(num_marshal_zones as usize);
+    let marshal_zones = marshal_zones;
+    let safety_car_status = match cursor.get_u8() {
+        1 => SafetyCarStatus::Full,
+        2 => SafetyCarStatus::Virtual,
+        3 => SafetyCarStatus::Formation,
+        _ => SafetyCarStatus::No,
+    };
+    let network_game = cursor.get_u8() != 0;
+    let num_weather_forecast_samples = cursor.get_u8();
+    let mut weather_forecast_samples: Vec<WeatherForecastSample> = (0..56)
+        .map(|_| parse_weather_forecast_sample(cursor))
+        .collect();
+    weather_forecast_samples.truncate(num_weather_forecast_samples as usize);
+    let weather_forecast_samples = weather_forecast_samples;
+    let forecast_accuracy = if cursor.get_u8() == 0 {
+        ForecastAccuracy::Perfect
+    } else {
+        ForecastAccuracy::Approximate
+    };
+    let ai_difficulty: u8 = cursor.get_u8();
+    let season_link_identifier = cursor.get_u32_le();
+    let weekend_link_identifier = cursor.get_u32_le();
+    let session_link_identifier = cursor.get_u32_le();
+    let pit_stop_window_ideal_lap = cursor.get_u8();
+    let pit_stop_window_latest_lap = cursor.get_u8();
+    let pit_stop_rejoin_position = cursor.get_u8();
+    let steering_assist = cursor.get_u8() != 0;
+    let braking_assist = match cursor.get_u8() {
+        0 => BrakingAssist::Off,
+        1 => BrakingAssist::Low,
+        2 => BrakingAssist::Medium,
+        _ => BrakingAssist::High,
+    };
+    let gearbox_assist = match cursor.get_u8() {
+        1 => GearboxAssist::Manual,
+        2 => GearboxAssist::ManualSuggested,
+        _ => GearboxAssist::Auto,
+    };
+    let pit_assist = cursor.get_u8() != 0;
+    let pit_release_assist = cursor.get_u8() != 0;
+    let ers_assist = cursor.get_u8() != 0;
+    let drs_assist = cursor.get_u8() != 0;
+    let dynamic_racing_line = match cursor.get_u8() {
+        0 => DynamicRacingLine::Off,
+        1 => DynamicRacingLine::Corners,
+        _ => DynamicRacingLine::Full,
+    };
+    let dynamic_racing_line_type = if cursor.get_u8() == 0 {
+        DynamicRacingLineType::TwoD
+    } else {
+        DynamicRacingLineType::ThreeD
+    };
+
+    let game_mode = parse_game_mode(cursor);
+    let ruleset = parse_ruleset(cursor);
+    let time_of_day = cursor.get_u32_le();
+    let session_length = parse_session_length(cursor);
+
+    Ok(SessionPacket {
+        header,
+        raw: None,
+        weather,
+        track_temperature,
+        air_temperature,
+        total_laps,
+        track_length,
+        session_type,
+        track,
+        formula,
+        session_time_left,
+        session_duration,
+        pit_speed_limit,
+        game_paused,
+        is_spectating,
+        spectator_car_index,
+        sli_pro_native_support,
+        marshal_zones,
+        safety_car_status,
+        network_game,
+        weather_forecast_samples,
+        forecast_accuracy,
+        ai_difficulty,
+        season_link_identifier,
+        weekend_link_identifier,
+        session_link_identifier,
+        pit_stop_window_ideal_lap,
+        pit_stop_window_latest_lap,
+        pit_stop_rejoin_position,
+        steering_assist,
+        braking_assist,
+        gearbox_assist,
+        pit_assist,
+        pit_release_assist,
+        ers_assist,
+        drs_assist,
+        dynamic_racing_line,
+        dynamic_racing_line_type,
+        game_mode,
+        ruleset,
+        time_of_day,
+        session_length,
+        speed_units_lead_player: None,
+        temperature_units_lead_player: None,
+        speed_units_secondary_player: None,
+        temperature_units_secondary_player: None,
+        num_safety_car_periods: None,
+        num_virtual_safety_car_periods: None,
+        num_red_flag_periods: None,
+        rules: None,
+        weekend_structure: Vec::new(),
+        sector_2_lap_distance_start: None,
+        sector_3_lap_distance_start: None,
+    })
+}
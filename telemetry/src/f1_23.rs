@@ -1,26 +1,32 @@
 use std::io::Cursor;
 
-use bytes::Bytes;
+use bytes::{BufMut, Bytes, BytesMut};
 
 use crate::packet::header::Header;
 
 use super::packet::{header::PacketId, Packet};
 
-mod car_status;
-mod car_telemetry;
-mod event;
-mod final_classification;
-mod header;
-mod lap_data;
-mod lobby_info;
-mod motion;
-mod participants;
-mod session;
-mod session_history;
+pub(crate) mod car_damage;
+pub(crate) mod car_setups;
+pub(crate) mod car_status;
+pub(crate) mod car_telemetry;
+pub(crate) mod event;
+pub(crate) mod final_classification;
+pub(crate) mod header;
+pub(crate) mod lap_data;
+pub(crate) mod lobby_info;
+pub(crate) mod motion;
+pub(crate) mod motion_ex;
+pub(crate) mod participants;
+pub(crate) mod session;
+pub(crate) mod session_history;
+pub(crate) mod tyre_sets;
+
+#[cfg(test)]
+mod tests;
 
 pub fn decode_twentythree(cursor: &mut Cursor<Bytes>) -> crate::Result<Packet> {
-    cursor.set_position(6);
-    let packet_id = header::parse_packet_id(cursor)?;
+    let packet_id = header::parse_header(cursor)?.packet_id;
     cursor.set_position(0);
 
     match packet_id {
@@ -31,7 +37,7 @@ pub fn decode_twentythree(cursor: &mut Cursor<Bytes>) -> crate::Result<Packet> {
         PacketId::Participants => Ok(Packet::Participants(
             participants::parse_participants_packet(cursor)?,
         )),
-        PacketId::CarSetups => Ok(Packet::CarSetups(header::parse_header(cursor)?)),
+        PacketId::CarSetups => Ok(Packet::CarSetups(car_setups::parse_car_setups_packet(cursor)?)),
         PacketId::CarTelemetry => Ok(Packet::CarTelemetry(car_telemetry::parse_car_telemetry(
             cursor,
         )?)),
@@ -40,15 +46,56 @@ pub fn decode_twentythree(cursor: &mut Cursor<Bytes>) -> crate::Result<Packet> {
             final_classification::parse_final_classification_packet(cursor)?,
         )),
         PacketId::LobbyInfo => Ok(Packet::LobbyInfo(lobby_info::parse_lobby_info(cursor)?)),
-        PacketId::CarDamage => Ok(Packet::CarDamage(header::parse_header(cursor)?)),
+        PacketId::CarDamage => Ok(Packet::CarDamage(car_damage::parse_car_damage_packet(cursor)?)),
         PacketId::SessionHistory => Ok(Packet::SessionHistory(
             session_history::parse_session_history_packet(cursor)?,
         )),
-        PacketId::MotionEx => Ok(Packet::MotionEx(header::parse_header(cursor)?)),
-        PacketId::TyreSets => Ok(Packet::TyreSets(header::parse_header(cursor)?)),
+        PacketId::MotionEx => Ok(Packet::MotionEx(motion_ex::parse_motion_ex_packet(cursor)?)),
+        PacketId::TyreSets => Ok(Packet::TyreSets(tyre_sets::parse_tyre_sets_packet(cursor)?)),
     }
 }
 
 pub fn decode_twentythree_header(cursor: &mut Cursor<Bytes>) -> crate::Result<Header> {
     header::parse_header(cursor)
 }
+
+pub fn encode_twentythree(packet: &Packet) -> Bytes {
+    let mut buf = BytesMut::new();
+
+    match packet {
+        Packet::Motion(p) => motion::write_motion_packet(&mut buf, p),
+        Packet::Session(p) => session::write_session_packet(&mut buf, p),
+        Packet::LapData(p) => lap_data::write_lap_data_packet(&mut buf, p),
+        Packet::Event(p) => event::write_event_packet(&mut buf, p),
+        Packet::Participants(p) => participants::write_participants_packet(&mut buf, p),
+        Packet::CarSetups(p) => car_setups::write_car_setups_packet(&mut buf, p),
+        Packet::CarTelemetry(p) => car_telemetry::write_car_telemetry_packet(&mut buf, p),
+        Packet::CarStatus(p) => car_status::write_car_status_packet(&mut buf, p),
+        Packet::FinalClassification(p) => {
+            final_classification::write_final_classification_packet(&mut buf, p)
+        }
+        Packet::LobbyInfo(p) => lobby_info::write_lobby_info_packet(&mut buf, p),
+        Packet::CarDamage(p) => car_damage::write_car_damage_packet(&mut buf, p),
+        Packet::SessionHistory(p) => session_history::write_session_history_packet(&mut buf, p),
+        Packet::TyreSets(p) => tyre_sets::write_tyre_sets_packet(&mut buf, p),
+        Packet::MotionEx(p) => motion_ex::write_motion_ex_packet(&mut buf, p),
+    }
+
+    buf.freeze()
+}
+
+/// Writes `count` entries of `size` bytes each, zero filling the slots past the end of `items`
+fn write_padded<T>(
+    buf: &mut BytesMut,
+    items: &[T],
+    count: usize,
+    size: usize,
+    write: impl Fn(&mut BytesMut, &T),
+) {
+    for i in 0..count {
+        match items.get(i) {
+            Some(item) => write(buf, item),
+            None => buf.put_bytes(0, size),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::Cursor;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::packet::car_damage::{CarDamageData, CarDamagePacket};
+
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
+
+pub fn parse_car_damage_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<CarDamagePacket> {
+    crate::check_packet_length(cursor, "car damage", 953)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_damage_data = (0..22).map(|_| parse_car_damage_data(cursor)).collect();
+
+    Ok(CarDamagePacket {
+        header,
+        raw: None,
+        car_damage_data,
+    })
+}
+
+pub fn parse_car_damage_data(cursor: &mut Cursor<Bytes>) -> CarDamageData {
+    let tyres_wear = [
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+    ];
+    let tyres_damage = [
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+    ];
+    let brakes_damage = [
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+        cursor.get_u8(),
+    ];
+    let front_left_wing_damage = cursor.get_u8();
+    let front_right_wing_damage = cursor.get_u8();
+    let rear_wing_damage = cursor.get_u8();
+    let floor_damage = cursor.get_u8();
+    let diffuser_damage = cursor.get_u8();
+    let sidepod_damage = cursor.get_u8();
+    let drs_fault = cursor.get_u8() != 0;
+    let ers_fault = cursor.get_u8() != 0;
+    let gearbox_damage = cursor.get_u8();
+    let engine_damage = cursor.get_u8();
+    let engine_mguh_wear = cursor.get_u8();
+    let engine_es_wear = cursor.get_u8();
+    let engine_ce_wear = cursor.get_u8();
+    let engine_ice_wear = cursor.get_u8();
+    let engine_mguk_wear = cursor.get_u8();
+    let engine_tc_wear = cursor.get_u8();
+    let engine_blown = cursor.get_u8() != 0;
+    let engine_seized = cursor.get_u8() != 0;
+
+    CarDamageData {
+        tyres_wear,
+        tyres_damage,
+        brakes_damage,
+        front_left_wing_damage,
+        front_right_wing_damage,
+        rear_wing_damage,
+        floor_damage,
+        diffuser_damage,
+        sidepod_damage,
+        drs_fault,
+        ers_fault,
+        gearbox_damage,
+        engine_damage,
+        engine_mguh_wear,
+        engine_es_wear,
+        engine_ce_wear,
+        engine_ice_wear,
+        engine_mguk_wear,
+        engine_tc_wear,
+        engine_blown,
+        engine_seized,
+    }
+}
+
+pub fn write_car_damage_packet(buf: &mut BytesMut, packet: &CarDamagePacket) {
+    write_header(buf, &packet.header);
+    write_padded(buf, &packet.car_damage_data, 22, 42, write_car_damage_data);
+}
+
+pub fn write_car_damage_data(buf: &mut BytesMut, data: &CarDamageData) {
+    for tyre_wear in data.tyres_wear {
+        buf.put_f32_le(tyre_wear);
+    }
+    buf.put_slice(&data.tyres_damage);
+    buf.put_slice(&data.brakes_damage);
+    buf.put_u8(data.front_left_wing_damage);
+    buf.put_u8(data.front_right_wing_damage);
+    buf.put_u8(data.rear_wing_damage);
+    buf.put_u8(data.floor_damage);
+    buf.put_u8(data.diffuser_damage);
+    buf.put_u8(data.sidepod_damage);
+    buf.put_u8(data.drs_fault.into());
+    buf.put_u8(data.ers_fault.into());
+    buf.put_u8(data.gearbox_damage);
+    buf.put_u8(data.engine_damage);
+    buf.put_u8(data.engine_mguh_wear);
+    buf.put_u8(data.engine_es_wear);
+    buf.put_u8(data.engine_ce_wear);
+    buf.put_u8(data.engine_ice_wear);
+    buf.put_u8(data.engine_mguk_wear);
+    buf.put_u8(data.engine_tc_wear);
+    buf.put_u8(data.engine_blown.into());
+    buf.put_u8(data.engine_seized.into());
+}
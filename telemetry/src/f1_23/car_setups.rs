@@ -0,0 +1,104 @@
+use std::io::Cursor;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::packet::car_setups::{CarSetupData, CarSetupsPacket};
+
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
+
+pub fn parse_car_setups_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<CarSetupsPacket> {
+    crate::check_packet_length(cursor, "car setups", 1107)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_setups = (0..22).map(|_| parse_car_setup_data(cursor)).collect();
+
+    Ok(CarSetupsPacket {
+        header,
+        raw: None,
+        car_setups,
+        next_front_wing_value: None,
+    })
+}
+
+pub fn parse_car_setup_data(cursor: &mut Cursor<Bytes>) -> CarSetupData {
+    let front_wing = cursor.get_u8();
+    let rear_wing = cursor.get_u8();
+    let on_throttle = cursor.get_u8();
+    let off_throttle = cursor.get_u8();
+    let front_camber = cursor.get_f32_le();
+    let rear_camber = cursor.get_f32_le();
+    let front_toe = cursor.get_f32_le();
+    let rear_toe = cursor.get_f32_le();
+    let front_suspension = cursor.get_u8();
+    let rear_suspension = cursor.get_u8();
+    let front_anti_roll_bar = cursor.get_u8();
+    let rear_anti_roll_bar = cursor.get_u8();
+    let front_suspension_height = cursor.get_u8();
+    let rear_suspension_height = cursor.get_u8();
+    let brake_pressure = cursor.get_u8();
+    let brake_bias = cursor.get_u8();
+    let tyres_pressure = [
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+    ];
+    let ballast = cursor.get_u8();
+    let fuel_load = cursor.get_f32_le();
+
+    CarSetupData {
+        front_wing,
+        rear_wing,
+        on_throttle,
+        off_throttle,
+        front_camber,
+        rear_camber,
+        front_toe,
+        rear_toe,
+        front_suspension,
+        rear_suspension,
+        front_anti_roll_bar,
+        rear_anti_roll_bar,
+        front_suspension_height,
+        rear_suspension_height,
+        brake_pressure,
+        brake_bias,
+        engine_braking: None,
+        tyres_pressure,
+        ballast,
+        fuel_load,
+    }
+}
+
+pub fn write_car_setups_packet(buf: &mut BytesMut, packet: &CarSetupsPacket) {
+    write_header(buf, &packet.header);
+    write_padded(buf, &packet.car_setups, 22, 49, write_car_setup_data);
+}
+
+pub fn write_car_setup_data(buf: &mut BytesMut, data: &CarSetupData) {
+    buf.put_u8(data.front_wing);
+    buf.put_u8(data.rear_wing);
+    buf.put_u8(data.on_throttle);
+    buf.put_u8(data.off_throttle);
+    buf.put_f32_le(data.front_camber);
+    buf.put_f32_le(data.rear_camber);
+    buf.put_f32_le(data.front_toe);
+    buf.put_f32_le(data.rear_toe);
+    buf.put_u8(data.front_suspension);
+    buf.put_u8(data.rear_suspension);
+    buf.put_u8(data.front_anti_roll_bar);
+    buf.put_u8(data.rear_anti_roll_bar);
+    buf.put_u8(data.front_suspension_height);
+    buf.put_u8(data.rear_suspension_height);
+    buf.put_u8(data.brake_pressure);
+    buf.put_u8(data.brake_bias);
+    for tyre_pressure in data.tyres_pressure {
+        buf.put_f32_le(tyre_pressure);
+    }
+    buf.put_u8(data.ballast);
+    buf.put_f32_le(data.fuel_load);
+}
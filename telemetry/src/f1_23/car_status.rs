@@ -1,29 +1,30 @@
 use std::io::Cursor;
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::packet::car_status::{
     CarStatusData, CarStatusPacket, ERSDeployMode, FuelMix, TractionControl, TyreCompound,
 };
 
-use super::{header::parse_header, session::parse_marshal_flag};
+use super::{
+    header::{parse_header, write_header},
+    session::{encode_marshal_flag, parse_marshal_flag},
+    write_padded,
+};
 
 pub fn parse_car_status(cursor: &mut Cursor<Bytes>) -> crate::Result<CarStatusPacket> {
-    if cursor.remaining() != 1239 {
-        return Err(crate::TelemetryError::InvalidPacket(
-            "invalid car status packet length".to_owned(),
-        ));
-    }
+    crate::check_packet_length(cursor, "car status", 1239)?;
     let header = parse_header(cursor)?;
     let car_status_data: Vec<_> = (0..22).map(|_| parse_car_status_data(cursor)).collect();
 
     Ok(CarStatusPacket {
         header,
+        raw: None,
         car_status_data,
     })
 }
 
-fn parse_car_status_data(cursor: &mut Cursor<Bytes>) -> CarStatusData {
+pub fn parse_car_status_data(cursor: &mut Cursor<Bytes>) -> CarStatusData {
     let traction_control = match cursor.get_u8() {
         1 => TractionControl::Medium,
         2 => TractionControl::Full,
@@ -89,8 +90,8 @@ fn parse_car_status_data(cursor: &mut Cursor<Bytes>) -> CarStatusData {
         ers_harvested_this_lap_mguh,
         ers_deployed_this_lap,
         network_paused,
-        engine_power_ice,
-        engine_power_mguk,
+        engine_power_ice: Some(engine_power_ice),
+        engine_power_mguk: Some(engine_power_mguk),
     }
 }
 
@@ -127,3 +128,87 @@ pub fn parse_tyre_compound_visual(compound: u8) -> TyreCompound {
         _ => TyreCompound::Hard,
     }
 }
+
+pub fn write_car_status_packet(buf: &mut BytesMut, packet: &CarStatusPacket) {
+    write_header(buf, &packet.header);
+    write_padded(buf, &packet.car_status_data, 22, 55, write_car_status_data);
+}
+
+pub fn write_car_status_data(buf: &mut BytesMut, data: &CarStatusData) {
+    buf.put_u8(match data.traction_control {
+        TractionControl::Off => 0,
+        TractionControl::Medium => 1,
+        TractionControl::Full => 2,
+    });
+    buf.put_u8(data.anti_lock_brakes.into());
+    buf.put_u8(match data.fuel_mix {
+        FuelMix::Lean => 0,
+        FuelMix::Standard => 1,
+        FuelMix::Rich => 2,
+        FuelMix::Max => 3,
+    });
+    buf.put_u8(data.front_brake_bias);
+    buf.put_u8(data.pit_limiter_status.into());
+    buf.put_f32_le(data.fuel_in_tank);
+    buf.put_f32_le(data.fuel_capacity);
+    buf.put_f32_le(data.fuel_remaining_laps);
+    buf.put_u16_le(data.max_rpm);
+    buf.put_u16_le(data.idle_rpm);
+    buf.put_u8(data.max_gears);
+    buf.put_u8(data.drs_allowed);
+    buf.put_u16_le(data.drs_activation_distance);
+    buf.put_u8(encode_tyre_compound_actual(data.actual_tyre_compound));
+    buf.put_u8(encode_tyre_compound_visual(data.visual_tyre_compound));
+    buf.put_u8(data.tyres_age_laps);
+    buf.put_i8(encode_marshal_flag(data.vehicle_fia_flags));
+    buf.put_f32_le(data.engine_power_ice.unwrap_or_default());
+    buf.put_f32_le(data.engine_power_mguk.unwrap_or_default());
+    buf.put_f32_le(data.ers_store_energy);
+    buf.put_u8(match data.ers_deploy_mode {
+        ERSDeployMode::None => 0,
+        ERSDeployMode::Medium => 1,
+        ERSDeployMode::Hotlap => 2,
+        ERSDeployMode::Overtake => 3,
+    });
+    buf.put_f32_le(data.ers_harvested_this_lap_mguk);
+    buf.put_f32_le(data.ers_harvested_this_lap_mguh);
+    buf.put_f32_le(data.ers_deployed_this_lap);
+    buf.put_u8(data.network_paused.into());
+}
+
+pub fn encode_tyre_compound_actual(compound: TyreCompound) -> u8 {
+    match compound {
+        TyreCompound::C5 => 16,
+        TyreCompound::C4 => 17,
+        TyreCompound::C3 => 18,
+        TyreCompound::C2 => 19,
+        TyreCompound::C1 => 20,
+        TyreCompound::C0 => 21,
+        TyreCompound::Inter => 7,
+        TyreCompound::Wet => 8,
+        TyreCompound::Dry => 9,
+        TyreCompound::SuperSoft => 11,
+        TyreCompound::Soft => 12,
+        TyreCompound::Medium => 13,
+        TyreCompound::Hard => 14,
+    }
+}
+
+pub fn encode_tyre_compound_visual(compound: TyreCompound) -> u8 {
+    match compound {
+        TyreCompound::Soft => 16,
+        TyreCompound::Medium => 17,
+        TyreCompound::Hard => 18,
+        TyreCompound::Inter => 7,
+        TyreCompound::Wet => 8,
+        TyreCompound::Dry => 9,
+        TyreCompound::SuperSoft => 19,
+        // Only used as actual compounds
+        TyreCompound::C5
+        | TyreCompound::C4
+        | TyreCompound::C3
+        | TyreCompound::C2
+        | TyreCompound::C1
+        | TyreCompound::C0 => 0,
+    }
+}
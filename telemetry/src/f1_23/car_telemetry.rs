@@ -1,19 +1,18 @@
 use std::io::Cursor;
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::packet::car_telemetry::{
     CarTelemetryData, CarTelemetryPacket, MFDPanelIndex, SurfaceType,
 };
 
-use super::header::parse_header;
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
 
 pub fn parse_car_telemetry(cursor: &mut Cursor<Bytes>) -> crate::Result<CarTelemetryPacket> {
-    if cursor.remaining() != 1352 {
-        return Err(crate::TelemetryError::InvalidPacket(
-            "invalid car telemetry packet length".to_owned(),
-        ));
-    }
+    crate::check_packet_length(cursor, "car telemetry", 1352)?;
 
     let header = parse_header(cursor)?;
 
@@ -26,6 +25,7 @@ pub fn parse_car_telemetry(cursor: &mut Cursor<Bytes>) -> crate::Result<CarTelem
 
     Ok(CarTelemetryPacket {
         header,
+        raw: None,
         car_telemetry_data,
         mfd_panel_index,
         mfd_panel_index_secondary_player,
@@ -33,7 +33,7 @@ pub fn parse_car_telemetry(cursor: &mut Cursor<Bytes>) -> crate::Result<CarTelem
     })
 }
 
-fn parse_car_telemetry_data(cursor: &mut Cursor<Bytes>) -> CarTelemetryData {
+pub fn parse_car_telemetry_data(cursor: &mut Cursor<Bytes>) -> CarTelemetryData {
     let speed = cursor.get_u16_le();
     let throttle = cursor.get_f32_le();
     let steer = cursor.get_f32_le();
@@ -96,7 +96,7 @@ fn parse_car_telemetry_data(cursor: &mut Cursor<Bytes>) -> CarTelemetryData {
     }
 }
 
-fn parse_mfd_panel_index(cursor: &mut Cursor<Bytes>) -> MFDPanelIndex {
+pub fn parse_mfd_panel_index(cursor: &mut Cursor<Bytes>) -> MFDPanelIndex {
     match cursor.get_u8() {
         0 => MFDPanelIndex::CarSetup,
         1 => MFDPanelIndex::Pits,
@@ -123,3 +123,72 @@ fn parse_surface_type(cursor: &mut Cursor<Bytes>) -> SurfaceType {
         _ => SurfaceType::Tarmac,
     }
 }
+
+pub fn write_car_telemetry_packet(buf: &mut BytesMut, packet: &CarTelemetryPacket) {
+    write_header(buf, &packet.header);
+    write_padded(
+        buf,
+        &packet.car_telemetry_data,
+        22,
+        60,
+        write_car_telemetry_data,
+    );
+    buf.put_u8(encode_mfd_panel_index(packet.mfd_panel_index));
+    buf.put_u8(encode_mfd_panel_index(
+        packet.mfd_panel_index_secondary_player,
+    ));
+    buf.put_i8(packet.suggested_gear);
+}
+
+pub fn write_car_telemetry_data(buf: &mut BytesMut, data: &CarTelemetryData) {
+    buf.put_u16_le(data.speed);
+    buf.put_f32_le(data.throttle);
+    buf.put_f32_le(data.steer);
+    buf.put_f32_le(data.brake);
+    buf.put_u8(data.clutch);
+    buf.put_i8(data.gear);
+    buf.put_u16_le(data.engine_rpm);
+    buf.put_u8(data.drs.into());
+    buf.put_u8(data.rev_lights_percent);
+    buf.put_u16_le(data.rev_lights_bit_value);
+    for brake_temperature in data.brakes_temperature {
+        buf.put_u16_le(brake_temperature);
+    }
+    buf.put_slice(&data.tyres_surface_temperature);
+    buf.put_slice(&data.tyres_inner_temperature);
+    buf.put_u16_le(data.engine_temperature);
+    for tyre_pressure in data.tyres_pressure {
+        buf.put_f32_le(tyre_pressure);
+    }
+    for surface_type in data.surface_type {
+        buf.put_u8(encode_surface_type(surface_type));
+    }
+}
+
+pub fn encode_mfd_panel_index(mfd_panel_index: MFDPanelIndex) -> u8 {
+    match mfd_panel_index {
+        MFDPanelIndex::CarSetup => 0,
+        MFDPanelIndex::Pits => 1,
+        MFDPanelIndex::Damage => 2,
+        MFDPanelIndex::Engine => 3,
+        MFDPanelIndex::Temperatures => 4,
+        MFDPanelIndex::Closed => 255,
+    }
+}
+
+fn encode_surface_type(surface_type: SurfaceType) -> u8 {
+    match surface_type {
+        SurfaceType::Tarmac => 0,
+        SurfaceType::RumbleStrip => 1,
+        SurfaceType::Concrete => 2,
+        SurfaceType::Rock => 3,
+        SurfaceType::Gravel => 4,
+        SurfaceType::Mud => 5,
+        SurfaceType::Sand => 6,
+        SurfaceType::Grass => 7,
+        SurfaceType::Water => 8,
+        SurfaceType::Cobblestone => 9,
+        SurfaceType::Metal => 10,
+        SurfaceType::Ridged => 11,
+    }
+}
@@ -1,25 +1,38 @@
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::{
     io::{Cursor, Read},
     time::Duration,
 };
 
 use crate::packet::event::{
-    Buttons, Event, EventPacket, FastestLap, Flashback, InfringementType, Overtake, Penalty, PenaltyType, SpeedTrap, StartLights, VehicleIdx
+    ButtonFlags, Buttons, Collision, Event, EventPacket, FastestLap, Flashback, InfringementType, Overtake, Penalty, PenaltyType, SafetyCar, SafetyCarEventType, SpeedTrap, StartLights, UnknownEvent, VehicleIdx
 };
 
-use super::header::parse_header;
+use super::{
+    header::{parse_header, write_header},
+    session::{encode_safety_car_status, parse_safety_car_status},
+};
 
 pub fn parse_event_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<EventPacket> {
+    crate::check_packet_length(cursor, "event", 45)?;
+
     let header = parse_header(cursor)?;
+    let event = parse_event(cursor)?;
+
+    Ok(EventPacket {
+        header,
+        event,
+        raw: None,
+    })
+}
 
+pub fn parse_event(cursor: &mut Cursor<Bytes>) -> crate::Result<Event> {
     let mut event_string_code: [u8; 4] = [0; 4];
     cursor.read_exact(&mut event_string_code)?;
-    let event_string_code = String::from_utf8_lossy(&event_string_code);
-    let event = match event_string_code.as_ref() {
+    let event = match String::from_utf8_lossy(&event_string_code).as_ref() {
         "SSTA" => Event::SessionStarted,
         "SEND" => Event::SessionEnded,
-        "FTLP" => Event::FastestLap(parse_fastest_lap(cursor)),
+        "FTLP" => Event::FastestLap(parse_fastest_lap(cursor)?),
         "RTMT" => Event::Retirement(parse_vehicle_idx(cursor)),
         "DRSE" => Event::DRSEnabled,
         "DRSD" => Event::DRSDisabled,
@@ -36,10 +49,12 @@ pub fn parse_event_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<EventPack
         "BUTN" => Event::Button(parse_buttons(cursor)),
         "RDFL" => Event::RedFlag,
         "OVTK" => Event::Overtake(parse_overtake(cursor)),
-        _ => Event::Unknown,
+        "SCAR" => Event::SafetyCar(parse_safety_car(cursor)),
+        "COLL" => Event::Collision(parse_collision(cursor)),
+        _ => Event::Unknown(parse_unknown_event(event_string_code, cursor)),
     };
 
-    Ok(EventPacket { header, event })
+    Ok(event)
 }
 
 fn parse_overtake(cursor: &mut Cursor<Bytes>) -> Overtake {
@@ -51,13 +66,44 @@ fn parse_overtake(cursor: &mut Cursor<Bytes>) -> Overtake {
     }
 }
 
-fn parse_fastest_lap(cursor: &mut Cursor<Bytes>) -> FastestLap {
+fn parse_safety_car(cursor: &mut Cursor<Bytes>) -> SafetyCar {
+    let safety_car_type = parse_safety_car_status(cursor);
+    let event_type = match cursor.get_u8() {
+        0 => SafetyCarEventType::Deployed,
+        1 => SafetyCarEventType::Returning,
+        2 => SafetyCarEventType::Returned,
+        3 => SafetyCarEventType::ResumeRace,
+        _ => SafetyCarEventType::Unknown,
+    };
+    SafetyCar {
+        safety_car_type,
+        event_type,
+    }
+}
+
+fn parse_collision(cursor: &mut Cursor<Bytes>) -> Collision {
+    let vehicle_1_idx = cursor.get_u8();
+    let vehicle_2_idx = cursor.get_u8();
+    Collision {
+        vehicle_1_idx,
+        vehicle_2_idx,
+    }
+}
+
+fn parse_unknown_event(code: [u8; 4], cursor: &mut Cursor<Bytes>) -> UnknownEvent {
+    let data = cursor.copy_to_bytes(cursor.remaining()).to_vec();
+    UnknownEvent { code, data }
+}
+
+fn parse_fastest_lap(cursor: &mut Cursor<Bytes>) -> crate::Result<FastestLap> {
     let vehicle_idx = cursor.get_u8();
-    let lap_time = Duration::from_secs_f32(cursor.get_f32_le());
-    FastestLap {
+    let lap_time = Duration::try_from_secs_f32(cursor.get_f32_le()).map_err(|e| {
+        crate::TelemetryError::InvalidPacket(format!("invalid fastest lap time: {e}"))
+    })?;
+    Ok(FastestLap {
         vehicle_idx,
         lap_time,
-    }
+    })
 }
 
 fn parse_vehicle_idx(cursor: &mut Cursor<Bytes>) -> VehicleIdx {
@@ -203,6 +249,190 @@ fn parse_flashback(cursor: &mut Cursor<Bytes>) -> Flashback {
 }
 
 fn parse_buttons(cursor: &mut Cursor<Bytes>) -> Buttons {
-    let button_status = cursor.get_u32_le();
+    let button_status = ButtonFlags::from_bits_retain(cursor.get_u32_le());
     Buttons { button_status }
 }
+
+pub fn write_event_packet(buf: &mut BytesMut, packet: &EventPacket) {
+    write_header(buf, &packet.header);
+    write_event(buf, &packet.event);
+}
+
+/// Writes the event code and its details, zero filled to the size of the largest event
+pub fn write_event(buf: &mut BytesMut, event: &Event) {
+    let start = buf.len();
+    match event {
+        Event::SessionStarted => buf.put_slice(b"SSTA"),
+        Event::SessionEnded => buf.put_slice(b"SEND"),
+        Event::FastestLap(fastest_lap) => {
+            buf.put_slice(b"FTLP");
+            buf.put_u8(fastest_lap.vehicle_idx);
+            buf.put_f32_le(fastest_lap.lap_time.as_secs_f32());
+        }
+        Event::Retirement(vehicle_idx) => {
+            buf.put_slice(b"RTMT");
+            buf.put_u8(vehicle_idx.0);
+        }
+        Event::DRSEnabled => buf.put_slice(b"DRSE"),
+        Event::DRSDisabled => buf.put_slice(b"DRSD"),
+        Event::TeamMateInPits(vehicle_idx) => {
+            buf.put_slice(b"TMPT");
+            buf.put_u8(vehicle_idx.0);
+        }
+        Event::ChequeredFlag => buf.put_slice(b"CHQF"),
+        Event::RaceWinner(vehicle_idx) => {
+            buf.put_slice(b"RCWN");
+            buf.put_u8(vehicle_idx.0);
+        }
+        Event::PenaltyIssued(penalty) => {
+            buf.put_slice(b"PENA");
+            buf.put_u8(encode_penalty_type(penalty.penalty_type));
+            buf.put_u8(encode_infringement_type(penalty.infringement_type));
+            buf.put_u8(penalty.vehicle_idx);
+            buf.put_u8(penalty.other_vehicle_idx);
+            buf.put_u8(penalty.time);
+            buf.put_u8(penalty.lap_num);
+            buf.put_u8(penalty.places_gained);
+        }
+        Event::SpeedTrapTriggered(speed_trap) => {
+            buf.put_slice(b"SPTP");
+            buf.put_u8(speed_trap.vehicle_idx);
+            buf.put_f32_le(speed_trap.speed);
+            buf.put_u8(speed_trap.is_overall_fastest_in_session.into());
+            buf.put_u8(speed_trap.is_driver_fastest_in_session.into());
+            buf.put_u8(speed_trap.fastest_vehicle_idx_in_session);
+            buf.put_f32_le(speed_trap.fastest_speed_in_session);
+        }
+        Event::StartLights(start_lights) => {
+            buf.put_slice(b"STLG");
+            buf.put_u8(start_lights.num_lights);
+        }
+        Event::LightsOut => buf.put_slice(b"LGOT"),
+        Event::DriveThroughServed(vehicle_idx) => {
+            buf.put_slice(b"DTSV");
+            buf.put_u8(vehicle_idx.0);
+        }
+        Event::StopGoServed => buf.put_slice(b"SGSV"),
+        Event::Flashback(flashback) => {
+            buf.put_slice(b"FLBK");
+            buf.put_u32_le(flashback.flashback_frame_identifier);
+            buf.put_f32_le(flashback.flashback_session_time);
+        }
+        Event::Button(buttons) => {
+            buf.put_slice(b"BUTN");
+            buf.put_u32_le(buttons.button_status.bits());
+        }
+        Event::RedFlag => buf.put_slice(b"RDFL"),
+        Event::Overtake(overtake) => {
+            buf.put_slice(b"OVTK");
+            buf.put_u8(overtake.overtaking_vehicle_idx);
+            buf.put_u8(overtake.being_overtaken_vehicle_idx);
+        }
+        Event::SafetyCar(safety_car) => {
+            buf.put_slice(b"SCAR");
+            buf.put_u8(encode_safety_car_status(safety_car.safety_car_type));
+            buf.put_u8(match safety_car.event_type {
+                SafetyCarEventType::Deployed => 0,
+                SafetyCarEventType::Returning => 1,
+                SafetyCarEventType::Returned => 2,
+                SafetyCarEventType::ResumeRace => 3,
+                SafetyCarEventType::Unknown => 255,
+            });
+        }
+        Event::Collision(collision) => {
+            buf.put_slice(b"COLL");
+            buf.put_u8(collision.vehicle_1_idx);
+            buf.put_u8(collision.vehicle_2_idx);
+        }
+        Event::Unknown(unknown) => {
+            buf.put_slice(&unknown.code);
+            buf.put_slice(&unknown.data[..unknown.data.len().min(12)]);
+        }
+    }
+    buf.put_bytes(0, 16 - (buf.len() - start));
+}
+
+fn encode_penalty_type(penalty_type: PenaltyType) -> u8 {
+    match penalty_type {
+        PenaltyType::DriveThrough => 0,
+        PenaltyType::StopGo => 1,
+        PenaltyType::GridPenalty => 2,
+        PenaltyType::PenaltyReminder => 3,
+        PenaltyType::TimePenalty => 4,
+        PenaltyType::Warning => 5,
+        PenaltyType::Disqualified => 6,
+        PenaltyType::RemovedFromFormationLap => 7,
+        PenaltyType::ParkedTooLongTimer => 8,
+        PenaltyType::TyreRegulations => 9,
+        PenaltyType::ThisLapInvalidated => 10,
+        PenaltyType::ThisAndNextLapInvalidated => 11,
+        PenaltyType::ThisLapInvalidatedWithoutReason => 12,
+        PenaltyType::ThisAndNextLapInvalidatedWithoutReason => 13,
+        PenaltyType::ThisAndPreviousLapInvalidated => 14,
+        PenaltyType::ThisAndPreviousLapInvalidatedWithoutReason => 15,
+        PenaltyType::Retired => 16,
+        PenaltyType::BlackFlagTimer => 17,
+        PenaltyType::Unknown => 255,
+    }
+}
+
+fn encode_infringement_type(infringement_type: InfringementType) -> u8 {
+    match infringement_type {
+        InfringementType::BlockingBySlowDriving => 0,
+        InfringementType::BlockingByWrongWayDriving => 1,
+        InfringementType::ReversingOffTheStartLine => 2,
+        InfringementType::BigCollision => 3,
+        InfringementType::SmallCollision => 4,
+        InfringementType::CollisionFailedToHandBackPositionSingle => 5,
+        InfringementType::CollisionFailedToHandBackPositionMultiple => 6,
+        InfringementType::CornerCuttingGainedTime => 7,
+        InfringementType::CornerCuttingOvertakeSingle => 8,
+        InfringementType::CornerCuttingOvertakeMultiple => 9,
+        InfringementType::CrossedPitExitLane => 10,
+        InfringementType::IgnoringBlueFlags => 11,
+        InfringementType::IgnoringYellowFlags => 12,
+        InfringementType::IgnoringDriveThrough => 13,
+        InfringementType::TooManyDriveThroughs => 14,
+        InfringementType::DriveThroughReminderServeWithinNLaps => 15,
+        InfringementType::DriveThroughReminderServeThisLap => 16,
+        InfringementType::PitLaneSpeeding => 17,
+        InfringementType::ParkedForTooLong => 18,
+        InfringementType::IgnoringTyreRegulations => 19,
+        InfringementType::TooManyPenalties => 20,
+        InfringementType::MultipleWarnings => 21,
+        InfringementType::ApproachingDisqualification => 22,
+        InfringementType::TyreRegulationsSelectSingle => 23,
+        InfringementType::TyreRegulationsSelectMultiple => 24,
+        InfringementType::LapInvalidatedCornerCutting => 25,
+        InfringementType::LapInvalidatedRunningWide => 26,
+        InfringementType::CornerCuttingRanWideGainedTimeMinor => 27,
+        InfringementType::CornerCuttingRanWideGainedTimeSignificant => 28,
+        InfringementType::CornerCuttingRanWideGainedTimeExtreme => 29,
+        InfringementType::LapInvalidatedWallRiding => 30,
+        InfringementType::LapInvalidatedFlashbackUsed => 31,
+        InfringementType::LapInvalidatedResetToTrack => 32,
+        InfringementType::BlockingThePitlane => 33,
+        InfringementType::JumpStart => 34,
+        InfringementType::SafetyCarToCarCollision => 35,
+        InfringementType::SafetyCarIllegalOvertake => 36,
+        InfringementType::SafetyCarExceedingAllowedPace => 37,
+        InfringementType::VirtualSafetyCarExceedingAllowedPace => 38,
+        InfringementType::FormationlapBelowAllowedSpeed => 39,
+        InfringementType::FormationLapParking => 40,
+        InfringementType::RetiredMechanicalFailure => 41,
+        InfringementType::RetiredTerminallyDamaged => 42,
+        InfringementType::SafetyCarFallingTooFarBack => 43,
+        InfringementType::BlackFlagTimer => 44,
+        InfringementType::UnservedStopGoPenalty => 45,
+        InfringementType::UnservedDriveThroughPenalty => 46,
+        InfringementType::EngineComponentChange => 47,
+        InfringementType::GearboxChange => 48,
+        InfringementType::ParcFermeChange => 49,
+        InfringementType::LeagueGridPenalty => 50,
+        InfringementType::RetryPenalty => 51,
+        InfringementType::IllegalTimeGain => 52,
+        InfringementType::MandatoryPitstop => 53,
+        InfringementType::AttributeAssigned => 54,
+        InfringementType::Unknown => 255,
+    }
+}
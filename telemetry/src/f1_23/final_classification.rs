@@ -1,23 +1,26 @@
 use std::{io::Cursor, time::Duration};
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::{
     packet::final_classification::{FinalClassificationData, FinalClassificationPacket, TyreStint},
     Result,
 };
 
-
-use super::{header::parse_header, lap_data::parse_result_data, car_status::{parse_tyre_compound_actual, parse_tyre_compound_visual}};
+use super::{
+    car_status::{
+        encode_tyre_compound_actual, encode_tyre_compound_visual, parse_tyre_compound_actual,
+        parse_tyre_compound_visual,
+    },
+    header::{parse_header, write_header},
+    lap_data::{encode_result_status, parse_result_data},
+    write_padded,
+};
 
 pub fn parse_final_classification_packet(
     cursor: &mut Cursor<Bytes>,
 ) -> Result<FinalClassificationPacket> {
-    if cursor.remaining() != 1020 {
-        return Err(crate::TelemetryError::InvalidPacket(
-            "invalid final classification packet length".to_owned(),
-        ));
-    }
+    crate::check_packet_length(cursor, "final classification", 1020)?;
 
     let header = parse_header(cursor)?;
     let _num_cars = cursor.get_u8();
@@ -36,11 +39,12 @@ pub fn parse_final_classification_packet(
 
     Ok(FinalClassificationPacket {
         header,
+        raw: None,
         classification_data,
     })
 }
 
-fn parse_final_classification_data(cursor: &mut Cursor<Bytes>) -> Result<FinalClassificationData> {
+pub fn parse_final_classification_data(cursor: &mut Cursor<Bytes>) -> Result<FinalClassificationData> {
     let position = cursor.get_u8();
     let num_laps = cursor.get_u8();
     let grid_position = cursor.get_u8();
@@ -48,10 +52,18 @@ fn parse_final_classification_data(cursor: &mut Cursor<Bytes>) -> Result<FinalCl
     let num_pit_stops = cursor.get_u8();
     let status = parse_result_data(cursor);
     let best_laptime = Duration::from_millis(cursor.get_u32_le() as _);
-    let total_race_time_without_penalties = Duration::from_secs_f64(cursor.get_f64_le());
+    let total_race_time_without_penalties = Duration::try_from_secs_f64(cursor.get_f64_le())
+        .map_err(|e| {
+            crate::TelemetryError::InvalidPacket(format!("invalid total race time: {e}"))
+        })?;
     let penalty_time_in_seconds = cursor.get_u8();
     let num_penalties = cursor.get_u8();
     let num_tyre_stints = cursor.get_u8();
+    if num_tyre_stints > 8 {
+        return Err(crate::TelemetryError::InvalidPacket(format!(
+            "invalid number of tyre stints in final classification packet: {num_tyre_stints}"
+        )));
+    }
     let tyre_data = cursor.copy_to_bytes(24);
     let tyre_stints = (0..num_tyre_stints as usize)
         .map(|i| {
@@ -80,3 +92,43 @@ fn parse_final_classification_data(cursor: &mut Cursor<Bytes>) -> Result<FinalCl
         tyre_stints,
     })
 }
+
+pub fn write_final_classification_packet(buf: &mut BytesMut, packet: &FinalClassificationPacket) {
+    write_header(buf, &packet.header);
+    let num_cars = packet.classification_data.iter().flatten().count();
+    buf.put_u8(num_cars as u8);
+    write_padded(
+        buf,
+        &packet.classification_data,
+        22,
+        45,
+        |buf, data| match data {
+            Some(data) => write_final_classification_data(buf, data),
+            None => buf.put_bytes(0, 45),
+        },
+    );
+}
+
+pub fn write_final_classification_data(buf: &mut BytesMut, data: &FinalClassificationData) {
+    buf.put_u8(data.position);
+    buf.put_u8(data.num_laps);
+    buf.put_u8(data.grid_position);
+    buf.put_u8(data.points);
+    buf.put_u8(data.num_pit_stops);
+    buf.put_u8(encode_result_status(data.status));
+    buf.put_u32_le(data.best_laptime.as_millis() as u32);
+    buf.put_f64_le(data.total_race_time_without_penalties.as_secs_f64());
+    buf.put_u8(data.penalty_time_in_seconds);
+    buf.put_u8(data.num_penalties);
+    let tyre_stints = &data.tyre_stints[..data.tyre_stints.len().min(8)];
+    buf.put_u8(tyre_stints.len() as u8);
+    write_padded(buf, tyre_stints, 8, 1, |buf, stint| {
+        buf.put_u8(encode_tyre_compound_actual(stint.actual_tyre))
+    });
+    write_padded(buf, tyre_stints, 8, 1, |buf, stint| {
+        buf.put_u8(encode_tyre_compound_visual(stint.visual_tyre))
+    });
+    write_padded(buf, tyre_stints, 8, 1, |buf, stint| {
+        buf.put_u8(stint.end_lap)
+    });
+}
@@ -1,4 +1,4 @@
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::io::Cursor;
 
 use crate::packet::header::{Format, GameVersion, Header, PacketId};
@@ -37,7 +37,7 @@ pub fn parse_header(cursor: &mut Cursor<Bytes>) -> crate::Result<Header> {
         session_uid,
         session_time,
         frame_identifier,
-        overall_frame_identifier,
+        overall_frame_identifier: Some(overall_frame_identifier),
         player_car_index,
         secondary_player_car_index,
     };
@@ -64,3 +64,41 @@ pub fn parse_packet_id(cursor: &mut Cursor<Bytes>) -> crate::Result<PacketId> {
         i => Err(crate::TelemetryError::InvalidPacket(format!("invalid packet id: {}", i))),
     }
 }
+
+pub fn write_header(buf: &mut BytesMut, header: &Header) {
+    buf.put_u16_le(2023);
+    buf.put_u8(header.game_year);
+    buf.put_u8(header.game_version.0);
+    buf.put_u8(header.game_version.1);
+    buf.put_u8(header.packet_version);
+    buf.put_u8(encode_packet_id(header.packet_id));
+    buf.put_u64_le(header.session_uid);
+    buf.put_f32_le(header.session_time);
+    buf.put_u32_le(header.frame_identifier);
+    buf.put_u32_le(
+        header
+            .overall_frame_identifier
+            .unwrap_or(header.frame_identifier),
+    );
+    buf.put_u8(header.player_car_index);
+    buf.put_u8(header.secondary_player_car_index.unwrap_or(255));
+}
+
+pub fn encode_packet_id(packet_id: PacketId) -> u8 {
+    match packet_id {
+        PacketId::Motion => 0,
+        PacketId::Session => 1,
+        PacketId::LapData => 2,
+        PacketId::Event => 3,
+        PacketId::Participants => 4,
+        PacketId::CarSetups => 5,
+        PacketId::CarTelemetry => 6,
+        PacketId::CarStatus => 7,
+        PacketId::FinalClassification => 8,
+        PacketId::LobbyInfo => 9,
+        PacketId::CarDamage => 10,
+        PacketId::SessionHistory => 11,
+        PacketId::TyreSets => 12,
+        PacketId::MotionEx => 13,
+    }
+}
@@ -1,13 +1,18 @@
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::{io::Cursor, time::Duration};
 
 use crate::packet::lap_data::{
     DriverStatus, LapData, LapDataPacket, PitStatus, ResultStatus, Sector,
 };
 
-use super::header::parse_header;
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
 
 pub fn parse_lap_data_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<LapDataPacket> {
+    crate::check_packet_length(cursor, "lap data", 1131)?;
+
     let header = parse_header(cursor)?;
     let lap_data: Vec<_> = (0..22).map(|_| parse_lap_data(cursor)).collect();
 
@@ -16,6 +21,7 @@ pub fn parse_lap_data_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<LapDat
 
     Ok(LapDataPacket {
         header,
+        raw: None,
         lap_data,
         time_trial_pb_car_idx,
         time_trial_rival_car_idx,
@@ -110,11 +116,13 @@ pub fn parse_lap_data(cursor: &mut Cursor<Bytes>) -> Option<LapData> {
         result_status,
         pit_lane_timer_active,
         pit_stop_should_serve_pen,
-        delta_to_car_in_front,
-        delta_to_race_leader,
-        corner_cutting_warnings,
+        delta_to_car_in_front: Some(delta_to_car_in_front),
+        delta_to_race_leader: Some(delta_to_race_leader),
+        corner_cutting_warnings: Some(corner_cutting_warnings),
         pit_lane_time_in_lane,
         pit_stop_timer,
+        speed_trap_fastest_speed: None,
+        speed_trap_fastest_lap: None,
     })
 }
 
@@ -130,3 +138,91 @@ pub fn parse_result_data(cursor: &mut Cursor<Bytes>) -> ResultStatus {
         _ => ResultStatus::Invalid,
     }
 }
+
+pub fn write_lap_data_packet(buf: &mut BytesMut, packet: &LapDataPacket) {
+    write_header(buf, &packet.header);
+    write_padded(
+        buf,
+        &packet.lap_data,
+        22,
+        50,
+        |buf, lap_data| match lap_data {
+            Some(lap_data) => write_lap_data(buf, lap_data),
+            None => buf.put_bytes(0, 50),
+        },
+    );
+    buf.put_u8(packet.time_trial_pb_car_idx.unwrap_or(255));
+    buf.put_u8(packet.time_trial_rival_car_idx.unwrap_or(255));
+}
+
+pub fn write_lap_data(buf: &mut BytesMut, lap_data: &LapData) {
+    buf.put_u32_le(lap_data.last_lap_time.as_millis() as u32);
+    buf.put_u32_le(lap_data.current_lap_time.as_millis() as u32);
+    write_split_time(buf, lap_data.sector_1_time);
+    write_split_time(buf, lap_data.sector_2_time);
+    buf.put_u16_le(
+        lap_data
+            .delta_to_car_in_front
+            .map_or(0, |delta| delta.as_millis() as u16),
+    );
+    buf.put_u16_le(
+        lap_data
+            .delta_to_race_leader
+            .map_or(0, |delta| delta.as_millis() as u16),
+    );
+    buf.put_f32_le(lap_data.lap_distance);
+    buf.put_f32_le(lap_data.total_distance);
+    buf.put_f32_le(lap_data.safety_car_delta);
+    buf.put_u8(lap_data.car_position);
+    buf.put_u8(lap_data.current_lap_num);
+    buf.put_u8(match lap_data.pit_status {
+        PitStatus::None => 0,
+        PitStatus::Pitting => 1,
+        PitStatus::InPitArea => 2,
+    });
+    buf.put_u8(lap_data.num_pit_stops);
+    buf.put_u8(match lap_data.sector {
+        Sector::Sector1 => 0,
+        Sector::Sector2 => 1,
+        Sector::Sector3 => 2,
+    });
+    buf.put_u8(lap_data.current_lap_invalid.into());
+    buf.put_u8(lap_data.penalties);
+    buf.put_u8(lap_data.warnings);
+    buf.put_u8(lap_data.corner_cutting_warnings.unwrap_or(0));
+    buf.put_u8(lap_data.num_unserved_drive_through_pens);
+    buf.put_u8(lap_data.num_unserved_stop_go_pens);
+    buf.put_u8(lap_data.grid_position);
+    buf.put_u8(match lap_data.driver_status {
+        DriverStatus::InGarage => 0,
+        DriverStatus::FlyingLap => 1,
+        DriverStatus::InLap => 2,
+        DriverStatus::OutLap => 3,
+        DriverStatus::OnTrack => 4,
+    });
+    buf.put_u8(encode_result_status(lap_data.result_status));
+    buf.put_u8(lap_data.pit_lane_timer_active.into());
+    buf.put_u16_le(lap_data.pit_lane_time_in_lane.as_millis() as u16);
+    buf.put_u16_le(lap_data.pit_stop_timer.as_millis() as u16);
+    buf.put_u8(lap_data.pit_stop_should_serve_pen.into());
+}
+
+/// Writes a time as its millisecond part followed by its minute part
+pub fn write_split_time(buf: &mut BytesMut, time: Duration) {
+    let time_in_ms = time.as_millis();
+    buf.put_u16_le((time_in_ms % 60000) as u16);
+    buf.put_u8((time_in_ms / 60000) as u8);
+}
+
+pub fn encode_result_status(result_status: ResultStatus) -> u8 {
+    match result_status {
+        ResultStatus::Invalid => 0,
+        ResultStatus::Inactive => 1,
+        ResultStatus::Active => 2,
+        ResultStatus::Finished => 3,
+        ResultStatus::DidNotFinish => 4,
+        ResultStatus::Disqualified => 5,
+        ResultStatus::NotClassified => 6,
+        ResultStatus::Retired => 7,
+    }
+}
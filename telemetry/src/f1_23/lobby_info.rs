@@ -1,30 +1,37 @@
-use std::io::{Cursor, Read};
+use std::io::Cursor;
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::{
-    packet::{lobby_info::{LobbyInfoData, LobbyInfoPacket, Status}, participants::Platform},
-    f1_23::participants::parse_team,
+    packet::lobby_info::{LobbyInfoData, LobbyInfoPacket, Status},
+    f1_23::participants::{
+        encode_platform, encode_team, parse_name, parse_platform, parse_team, write_name,
+    },
     Result,
 };
 
-use super::header::parse_header;
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
 
 pub fn parse_lobby_info(cursor: &mut Cursor<Bytes>) -> Result<LobbyInfoPacket> {
-    if cursor.remaining() != 1218 {
-        return Err(crate::TelemetryError::InvalidPacket(
-            "invalid lobby info packet length".to_owned(),
-        ));
-    };
+    crate::check_packet_length(cursor, "lobby info", 1218)?;
 
     let header = parse_header(cursor)?;
     let num_players = cursor.get_u8();
+    if num_players > 22 {
+        return Err(crate::TelemetryError::InvalidPacket(format!(
+            "invalid number of players in lobby info packet: {num_players}"
+        )));
+    }
     let lobby_players = (0..num_players)
         .map(|_| parse_lobby_info_data(cursor))
         .collect::<Result<Vec<_>>>()?;
 
     Ok(LobbyInfoPacket {
         header,
+        raw: None,
         lobby_players,
     })
 }
@@ -33,17 +40,8 @@ fn parse_lobby_info_data(cursor: &mut Cursor<Bytes>) -> Result<LobbyInfoData> {
     let ai_controlled: bool = cursor.get_u8() != 0;
     let team = parse_team(cursor.get_u8());
     let nationality = cursor.get_u8();
-    let platform  = match cursor.get_u8() {
-        1 => Some(Platform::Steam),
-        3 => Some(Platform::PlayStation),
-        4 => Some(Platform::Xbox),
-        6 => Some(Platform::Origin),
-        _ => None,
-    };
-    let mut name = [0_u8; 48];
-    let _ = cursor.read_exact(&mut name); // Shouldn't error if the packet is not malformed
-    let name_end = name.iter().position(|&c| c == 0).unwrap_or(48);
-    let name = String::from_utf8_lossy(&name[0..name_end]).to_string().replace('\u{00a0}', " ");
+    let platform = parse_platform(cursor.get_u8());
+    let name = parse_name(cursor);
     let car_number = cursor.get_u8();
     let ready_status = match cursor.get_u8() {
         0 => Status::NotReady,
@@ -59,6 +57,30 @@ fn parse_lobby_info_data(cursor: &mut Cursor<Bytes>) -> Result<LobbyInfoData> {
         platform,
         name,
         car_number,
+        your_telemetry: None,
+        show_online_names: None,
+        tech_level: None,
         ready_status,
     })
 }
+
+pub fn write_lobby_info_packet(buf: &mut BytesMut, packet: &LobbyInfoPacket) {
+    write_header(buf, &packet.header);
+    let lobby_players = &packet.lobby_players[..packet.lobby_players.len().min(22)];
+    buf.put_u8(lobby_players.len() as u8);
+    write_padded(buf, lobby_players, 22, 54, write_lobby_info_data);
+}
+
+fn write_lobby_info_data(buf: &mut BytesMut, data: &LobbyInfoData) {
+    buf.put_u8(data.ai_controlled.into());
+    buf.put_u8(encode_team(data.team));
+    buf.put_u8(data.nationality);
+    buf.put_u8(encode_platform(data.platform));
+    write_name(buf, &data.name);
+    buf.put_u8(data.car_number);
+    buf.put_u8(match data.ready_status {
+        Status::NotReady => 0,
+        Status::Ready => 1,
+        Status::Spectating => 2,
+    });
+}
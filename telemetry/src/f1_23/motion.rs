@@ -1,15 +1,14 @@
 use crate::packet::motion::{CarMotionData, MotionPacket};
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::io::Cursor;
 
-use super::header::parse_header;
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
 
 pub fn parse_motion_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<MotionPacket> {
-    if cursor.remaining() != 1349 {
-        return Err(crate::TelemetryError::InvalidPacket(
-            "invalid motion packet length".to_owned(),
-        ));
-    }
+    crate::check_packet_length(cursor, "motion", 1349)?;
 
     let header = parse_header(cursor)?;
 
@@ -17,11 +16,12 @@ pub fn parse_motion_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<MotionPa
 
     Ok(MotionPacket {
         header,
+        raw: None,
         car_motion_data,
     })
 }
 
-fn parse_car_motion_data(cursor: &mut Cursor<Bytes>) -> CarMotionData {
+pub fn parse_car_motion_data(cursor: &mut Cursor<Bytes>) -> CarMotionData {
     let world_position_x = cursor.get_f32_le();
     let world_position_y = cursor.get_f32_le();
     let world_position_z = cursor.get_f32_le();
@@ -62,3 +62,29 @@ fn parse_car_motion_data(cursor: &mut Cursor<Bytes>) -> CarMotionData {
         roll,
     }
 }
+
+pub fn write_motion_packet(buf: &mut BytesMut, packet: &MotionPacket) {
+    write_header(buf, &packet.header);
+    write_padded(buf, &packet.car_motion_data, 22, 60, write_car_motion_data);
+}
+
+pub fn write_car_motion_data(buf: &mut BytesMut, data: &CarMotionData) {
+    buf.put_f32_le(data.world_position_x);
+    buf.put_f32_le(data.world_position_y);
+    buf.put_f32_le(data.world_position_z);
+    buf.put_f32_le(data.world_velocity_x);
+    buf.put_f32_le(data.world_velocity_y);
+    buf.put_f32_le(data.world_velocity_z);
+    buf.put_i16_le(data.world_forward_dir_x);
+    buf.put_i16_le(data.world_forward_dir_y);
+    buf.put_i16_le(data.world_forward_dir_z);
+    buf.put_i16_le(data.world_right_dir_x);
+    buf.put_i16_le(data.world_right_dir_y);
+    buf.put_i16_le(data.world_right_dir_z);
+    buf.put_f32_le(data.g_force_lateral);
+    buf.put_f32_le(data.g_force_longitudinal);
+    buf.put_f32_le(data.g_force_vertical);
+    buf.put_f32_le(data.yaw);
+    buf.put_f32_le(data.pitch);
+    buf.put_f32_le(data.roll);
+}
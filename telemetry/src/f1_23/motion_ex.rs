@@ -0,0 +1,107 @@
+use std::io::Cursor;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::packet::{header::Header, motion_ex::MotionExPacket};
+
+use super::header::{parse_header, write_header};
+
+pub fn parse_motion_ex_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<MotionExPacket> {
+    crate::check_packet_length(cursor, "motion ex", 217)?;
+
+    let header = parse_header(cursor)?;
+
+    Ok(parse_motion_ex_data(cursor, header))
+}
+
+pub fn parse_motion_ex_data(cursor: &mut Cursor<Bytes>, header: Header) -> MotionExPacket {
+    let suspension_position = parse_wheel_data(cursor);
+    let suspension_velocity = parse_wheel_data(cursor);
+    let suspension_acceleration = parse_wheel_data(cursor);
+    let wheel_speed = parse_wheel_data(cursor);
+    let wheel_slip_ratio = parse_wheel_data(cursor);
+    let wheel_slip_angle = parse_wheel_data(cursor);
+    let wheel_lat_force = parse_wheel_data(cursor);
+    let wheel_long_force = parse_wheel_data(cursor);
+    let height_of_cog_above_ground = cursor.get_f32_le();
+    let local_velocity_x = cursor.get_f32_le();
+    let local_velocity_y = cursor.get_f32_le();
+    let local_velocity_z = cursor.get_f32_le();
+    let angular_velocity_x = cursor.get_f32_le();
+    let angular_velocity_y = cursor.get_f32_le();
+    let angular_velocity_z = cursor.get_f32_le();
+    let angular_acceleration_x = cursor.get_f32_le();
+    let angular_acceleration_y = cursor.get_f32_le();
+    let angular_acceleration_z = cursor.get_f32_le();
+    let front_wheels_angle = cursor.get_f32_le();
+    let wheel_vert_force = parse_wheel_data(cursor);
+
+    MotionExPacket {
+        header,
+        raw: None,
+        suspension_position,
+        suspension_velocity,
+        suspension_acceleration,
+        wheel_speed,
+        wheel_slip_ratio,
+        wheel_slip_angle,
+        wheel_lat_force,
+        wheel_long_force,
+        height_of_cog_above_ground,
+        local_velocity_x,
+        local_velocity_y,
+        local_velocity_z,
+        angular_velocity_x,
+        angular_velocity_y,
+        angular_velocity_z,
+        angular_acceleration_x,
+        angular_acceleration_y,
+        angular_acceleration_z,
+        front_wheels_angle,
+        wheel_vert_force,
+        front_aero_height: None,
+        rear_aero_height: None,
+        front_roll_angle: None,
+        rear_roll_angle: None,
+        chassis_yaw: None,
+    }
+}
+
+fn parse_wheel_data(cursor: &mut Cursor<Bytes>) -> [f32; 4] {
+    [
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+    ]
+}
+
+pub fn write_motion_ex_packet(buf: &mut BytesMut, packet: &MotionExPacket) {
+    write_header(buf, &packet.header);
+    write_wheel_data(buf, packet.suspension_position);
+    write_wheel_data(buf, packet.suspension_velocity);
+    write_wheel_data(buf, packet.suspension_acceleration);
+    write_wheel_data(buf, packet.wheel_speed);
+    write_wheel_data(buf, packet.wheel_slip_ratio);
+    write_wheel_data(buf, packet.wheel_slip_angle);
+    write_wheel_data(buf, packet.wheel_lat_force);
+    write_wheel_data(buf, packet.wheel_long_force);
+    buf.put_f32_le(packet.height_of_cog_above_ground);
+    buf.put_f32_le(packet.local_velocity_x);
+    buf.put_f32_le(packet.local_velocity_y);
+    buf.put_f32_le(packet.local_velocity_z);
+    buf.put_f32_le(packet.angular_velocity_x);
+    buf.put_f32_le(packet.angular_velocity_y);
+    buf.put_f32_le(packet.angular_velocity_z);
+    buf.put_f32_le(packet.angular_acceleration_x);
+    buf.put_f32_le(packet.angular_acceleration_y);
+    buf.put_f32_le(packet.angular_acceleration_z);
+    buf.put_f32_le(packet.front_wheels_angle);
+    write_wheel_data(buf, packet.wheel_vert_force);
+}
+
+fn write_wheel_data(buf: &mut BytesMut, data: [f32; 4]) {
+    for value in data {
+        buf.put_f32_le(value);
+    }
+}
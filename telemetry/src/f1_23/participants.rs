@@ -1,18 +1,16 @@
 use std::io::{Cursor, Read};
 
-use bytes::{Buf, Bytes};
-use serde::__private::from_utf8_lossy;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::packet::participants::{ParticipantData, ParticipantsPacket, Platform, Team, Telemetry};
 
-use super::header::parse_header;
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
 
 pub fn parse_participants_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<ParticipantsPacket> {
-    if cursor.remaining() != 1306 {
-        return Err(crate::TelemetryError::InvalidPacket(
-            "Invalid participants packet length".to_owned(),
-        ));
-    }
+    crate::check_packet_length(cursor, "participants", 1306)?;
     let header = parse_header(cursor)?;
 
     let num_active_cars = cursor.get_u8();
@@ -20,6 +18,7 @@ pub fn parse_participants_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<Pa
 
     Ok(ParticipantsPacket {
         header,
+        raw: None,
         num_active_cars,
         participants,
     })
@@ -32,7 +31,41 @@ fn parse_participants_data(cursor: &mut Cursor<Bytes>) -> ParticipantData {
     let team_id = parse_team(cursor.get_u8());
     let my_team = cursor.get_u8() != 0;
     let race_number = cursor.get_u8();
-    let nationality = match cursor.get_u8() {
+    let nationality = parse_nationality(cursor.get_u8());
+    let name = parse_name(cursor);
+    let your_telemetry = if cursor.get_u8() == 1 {
+        Telemetry::Public
+    } else {
+        Telemetry::Restricted
+    };
+    let show_online_names = cursor.get_u8() != 0;
+    let platform = parse_platform(cursor.get_u8());
+
+    ParticipantData {
+        ai_controlled,
+        driver_id,
+        network_id,
+        team: team_id,
+        my_team,
+        race_number,
+        nationality,
+        name,
+        your_telemetry,
+        show_online_names: Some(show_online_names),
+        tech_level: None,
+        platform
+    }
+}
+
+pub fn parse_name(cursor: &mut Cursor<Bytes>) -> String {
+    let mut name = [0_u8; 48];
+    let _ = cursor.read_exact(&mut name); // Shouldn't error if the packet is not malformed
+    let name_end = name.iter().position(|&c| c == 0).unwrap_or(48);
+    String::from_utf8_lossy(&name[0..name_end]).to_string().replace('\u{00a0}', " ")
+}
+
+pub fn parse_nationality(nationality_id: u8) -> Option<celes::Country> {
+    match nationality_id {
         1 => Some(celes::Country::the_united_states_of_america()),
         2 => Some(celes::Country::argentina()),
         3 => Some(celes::Country::australia()),
@@ -121,37 +154,16 @@ fn parse_participants_data(cursor: &mut Cursor<Bytes>) -> ParticipantData {
         86 => Some(celes::Country::the_united_kingdom_of_great_britain_and_northern_ireland()),
         87 => Some(celes::Country::vietnam()),
         _ => None,
-    };
-    let mut name = [0_u8; 48];
-    let _ = cursor.read_exact(&mut name); // Shouldn't error if the packet is not malformed
-    let name_end = name.iter().position(|&c| c == 0).unwrap_or(48);
-    let name = from_utf8_lossy(&name[0..name_end]).to_string().replace('\u{00a0}', " ");
-    let your_telemetry = if cursor.get_u8() == 1 {
-        Telemetry::Public
-    } else {
-        Telemetry::Restricted
-    };
-    let show_online_names = cursor.get_u8() != 0;
-    let platform = match cursor.get_u8() {
+    }
+}
+
+pub fn parse_platform(platform_id: u8) -> Option<Platform> {
+    match platform_id {
         1 => Some(Platform::Steam),
         3 => Some(Platform::PlayStation),
         4 => Some(Platform::Xbox),
         6 => Some(Platform::Origin),
         _ => None,
-    };
-
-    ParticipantData {
-        ai_controlled,
-        driver_id,
-        network_id,
-        team: team_id,
-        my_team,
-        race_number,
-        nationality,
-        name,
-        your_telemetry,
-        show_online_names,
-        platform
     }
 }
 
@@ -189,3 +201,97 @@ pub fn parse_team(team_id: u8) -> Team {
         _ => Team::Unknown,
     }
 }
+
+pub fn write_participants_packet(buf: &mut BytesMut, packet: &ParticipantsPacket) {
+    write_header(buf, &packet.header);
+    buf.put_u8(packet.num_active_cars);
+    write_padded(buf, &packet.participants, 22, 58, write_participants_data);
+}
+
+fn write_participants_data(buf: &mut BytesMut, data: &ParticipantData) {
+    buf.put_u8(data.ai_controlled.into());
+    buf.put_u8(data.driver_id);
+    buf.put_u8(data.network_id);
+    buf.put_u8(encode_team(data.team));
+    buf.put_u8(data.my_team.into());
+    buf.put_u8(data.race_number);
+    buf.put_u8(encode_nationality(data.nationality));
+    write_name(buf, &data.name);
+    buf.put_u8(match data.your_telemetry {
+        Telemetry::Restricted => 0,
+        Telemetry::Public => 1,
+    });
+    buf.put_u8(data.show_online_names.unwrap_or(false).into());
+    buf.put_u8(encode_platform(data.platform));
+}
+
+/// Writes a name as a null terminated string of 48 bytes, cutting it off if it is too long
+pub fn write_name(buf: &mut BytesMut, name: &str) {
+    let mut name_end = name.len().min(47);
+    while !name.is_char_boundary(name_end) {
+        name_end -= 1;
+    }
+    buf.put_slice(&name.as_bytes()[..name_end]);
+    buf.put_bytes(0, 48 - name_end);
+}
+
+/// Countries with several nationalities (e.g. British and English) are written as the first one
+pub fn encode_nationality(nationality: Option<celes::Country>) -> u8 {
+    nationality
+        .and_then(|nationality| {
+            (1..=87).find(|&nationality_id| parse_nationality(nationality_id) == Some(nationality))
+        })
+        .unwrap_or(0)
+}
+
+pub fn encode_platform(platform: Option<Platform>) -> u8 {
+    match platform {
+        Some(Platform::Steam) => 1,
+        Some(Platform::PlayStation) => 3,
+        Some(Platform::Xbox) => 4,
+        Some(Platform::Origin) => 6,
+        None => 255,
+    }
+}
+
+/// Teams with several ids are written as the current season's id
+pub fn encode_team(team: Team) -> u8 {
+    match team {
+        Team::Mercedes => 0,
+        Team::Ferrari => 1,
+        Team::RedBullRacing => 2,
+        Team::Williams => 3,
+        Team::AstonMartin => 4,
+        Team::Alpine => 5,
+        Team::AlphaTauri => 6,
+        Team::Haas => 7,
+        Team::McLaren => 8,
+        Team::AlfaRomeo => 9,
+        Team::RacingPoint => 89,
+        Team::Renault => 90,
+        Team::Supercar => 95,
+        Team::SafetyCar => 97,
+        Team::CustomTeam => 104,
+        Team::BWT => 115,
+        Team::Konnersport => 128,
+        Team::Prema => 130,
+        Team::UniVirtuosi => 131,
+        Team::Carlin => 132,
+        Team::MPMotorsport => 133,
+        Team::Charouz => 134,
+        Team::Dams => 135,
+        Team::Campos => 136,
+        Team::Trident => 138,
+        Team::Hitech => 139,
+        Team::ArtGP => 140,
+        Team::Unknown
+        | Team::RB
+        | Team::Sauber
+        | Team::SauberJuniorCharouz
+        | Team::Virtuosi
+        | Team::Arden
+        | Team::PHM
+        | Team::VanAmersfoortRacing
+        | Team::F1Generic => 255,
+    }
+}
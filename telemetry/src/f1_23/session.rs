@@ -1,4 +1,4 @@
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use std::io::Cursor;
 
@@ -9,14 +9,13 @@ use crate::packet::session::{
     WeatherForecastSample,
 };
 
-use super::header::parse_header;
+use super::{
+    header::{parse_header, write_header},
+    write_padded,
+};
 
 pub fn parse_session_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<SessionPacket> {
-    if cursor.remaining() != 644 {
-        return Err(crate::TelemetryError::InvalidPacket(
-            "invalid session packet length".to_owned(),
-        ));
-    }
+    crate::check_packet_length(cursor, "session", 644)?;
 
     let header = parse_header(cursor)?;
 
@@ -48,12 +47,7 @@ pub fn parse_session_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<Session
     let mut marshal_zones: Vec<MarshalZone> = (0..21).map(|_| parse_marshal_zone(cursor)).collect();
     marshal_zones.truncate(num_marshal_zones as usize);
     let marshal_zones = marshal_zones;
-    let safety_car_status = match cursor.get_u8() {
-        1 => SafetyCarStatus::Full,
-        2 => SafetyCarStatus::Virtual,
-        3 => SafetyCarStatus::Formation,
-        _ => SafetyCarStatus::No,
-    };
+    let safety_car_status = parse_safety_car_status(cursor);
     let network_game = cursor.get_u8() != 0;
     let num_weather_forecast_samples = cursor.get_u8();
     let mut weather_forecast_samples: Vec<WeatherForecastSample> = (0..56)
@@ -131,6 +125,7 @@ pub fn parse_session_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<Session
 
     Ok(SessionPacket {
         header,
+        raw: None,
         weather,
         track_temperature,
         air_temperature,
@@ -171,17 +166,21 @@ pub fn parse_session_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<Session
         ruleset,
         time_of_day,
         session_length,
-        speed_units_lead_player,
-        temperature_units_lead_player,
-        speed_units_secondary_player,
-        temperature_units_secondary_player,
-        num_safety_car_periods,
-        num_virtual_safety_car_periods,
-        num_red_flag_periods,
+        speed_units_lead_player: Some(speed_units_lead_player),
+        temperature_units_lead_player: Some(temperature_units_lead_player),
+        speed_units_secondary_player: Some(speed_units_secondary_player),
+        temperature_units_secondary_player: Some(temperature_units_secondary_player),
+        num_safety_car_periods: Some(num_safety_car_periods),
+        num_virtual_safety_car_periods: Some(num_virtual_safety_car_periods),
+        num_red_flag_periods: Some(num_red_flag_periods),
+        rules: None,
+        weekend_structure: Vec::new(),
+        sector_2_lap_distance_start: None,
+        sector_3_lap_distance_start: None,
     })
 }
 
-fn parse_track(cursor: &mut Cursor<Bytes>) -> Track {
+pub fn parse_track(cursor: &mut Cursor<Bytes>) -> Track {
     match cursor.get_i8() {
         -1 => Track::Unknown,
         0 => Track::Melbourne,
@@ -231,7 +230,7 @@ pub fn parse_marshal_flag(cursor: &mut Cursor<Bytes>) -> MarshalFlag {
     }
 }
 
-fn parse_marshal_zone(cursor: &mut Cursor<Bytes>) -> MarshalZone {
+pub fn parse_marshal_zone(cursor: &mut Cursor<Bytes>) -> MarshalZone {
     let zone_start = cursor.get_f32_le();
     let zone_flag = parse_marshal_flag(cursor);
 
@@ -241,7 +240,7 @@ fn parse_marshal_zone(cursor: &mut Cursor<Bytes>) -> MarshalZone {
     }
 }
 
-fn parse_session_type(cursor: &mut Cursor<Bytes>) -> SessionType {
+pub fn parse_session_type(cursor: &mut Cursor<Bytes>) -> SessionType {
     match cursor.get_u8() {
         1 => SessionType::P1,
         2 => SessionType::P2,
@@ -260,7 +259,7 @@ fn parse_session_type(cursor: &mut Cursor<Bytes>) -> SessionType {
     }
 }
 
-fn parse_weather(cursor: &mut Cursor<Bytes>) -> Weather {
+pub fn parse_weather(cursor: &mut Cursor<Bytes>) -> Weather {
     match cursor.get_u8() {
         1 => Weather::LightCloud,
         2 => Weather::Overcast,
@@ -271,7 +270,7 @@ fn parse_weather(cursor: &mut Cursor<Bytes>) -> Weather {
     }
 }
 
-fn parse_weather_forecast_sample(cursor: &mut Cursor<Bytes>) -> WeatherForecastSample {
+pub fn parse_weather_forecast_sample(cursor: &mut Cursor<Bytes>) -> WeatherForecastSample {
     let session_type = parse_session_type(cursor);
     let time_offset = cursor.get_u8();
     let weather = parse_weather(cursor);
@@ -302,7 +301,7 @@ fn parse_weather_forecast_sample(cursor: &mut Cursor<Bytes>) -> WeatherForecastS
     }
 }
 
-fn parse_game_mode(cursor: &mut Cursor<Bytes>) -> GameMode {
+pub fn parse_game_mode(cursor: &mut Cursor<Bytes>) -> GameMode {
     match cursor.get_u8() {
         0 => GameMode::EventMode,
         3 => GameMode::GrandPrix,
@@ -326,7 +325,7 @@ fn parse_game_mode(cursor: &mut Cursor<Bytes>) -> GameMode {
     }
 }
 
-fn parse_ruleset(cursor: &mut Cursor<Bytes>) -> Ruleset {
+pub fn parse_ruleset(cursor: &mut Cursor<Bytes>) -> Ruleset {
     match cursor.get_u8() {
         0 => Ruleset::PracticeAndQualifying,
         1 => Ruleset::Race,
@@ -341,7 +340,7 @@ fn parse_ruleset(cursor: &mut Cursor<Bytes>) -> Ruleset {
     }
 }
 
-fn parse_session_length(cursor: &mut Cursor<Bytes>) -> SessionLength {
+pub fn parse_session_length(cursor: &mut Cursor<Bytes>) -> SessionLength {
     match cursor.get_u8() {
         0 => SessionLength::None,
         2 => SessionLength::VeryShort,
@@ -353,3 +352,291 @@ fn parse_session_length(cursor: &mut Cursor<Bytes>) -> SessionLength {
         _ => SessionLength::None,
     }
 }
+
+pub fn parse_safety_car_status(cursor: &mut Cursor<Bytes>) -> SafetyCarStatus {
+    match cursor.get_u8() {
+        1 => SafetyCarStatus::Full,
+        2 => SafetyCarStatus::Virtual,
+        3 => SafetyCarStatus::Formation,
+        _ => SafetyCarStatus::No,
+    }
+}
+
+pub fn write_session_packet(buf: &mut BytesMut, packet: &SessionPacket) {
+    write_header(buf, &packet.header);
+    buf.put_u8(encode_weather(packet.weather));
+    buf.put_i8(packet.track_temperature);
+    buf.put_i8(packet.air_temperature);
+    buf.put_u8(packet.total_laps);
+    buf.put_u16_le(packet.track_length);
+    buf.put_u8(encode_session_type(packet.session_type));
+    buf.put_i8(encode_track(packet.track));
+    buf.put_u8(match packet.formula {
+        Formula::F1Modern => 0,
+        Formula::F1Classic => 1,
+        Formula::F2 => 2,
+        Formula::F1Generic => 3,
+        Formula::Beta => 4,
+        Formula::Supercars => 5,
+        Formula::Esports => 6,
+        Formula::F2_2021 => 7,
+        Formula::F1World | Formula::F1Elimination => 0,
+    });
+    buf.put_u16_le(packet.session_time_left);
+    buf.put_u16_le(packet.session_duration);
+    buf.put_u8(packet.pit_speed_limit);
+    buf.put_u8(packet.game_paused.into());
+    buf.put_u8(packet.is_spectating.into());
+    buf.put_u8(packet.spectator_car_index);
+    buf.put_u8(packet.sli_pro_native_support.into());
+    let marshal_zones = &packet.marshal_zones[..packet.marshal_zones.len().min(21)];
+    buf.put_u8(marshal_zones.len() as u8);
+    write_padded(buf, marshal_zones, 21, 5, write_marshal_zone);
+    buf.put_u8(encode_safety_car_status(packet.safety_car_status));
+    buf.put_u8(packet.network_game.into());
+    let weather_forecast_samples =
+        &packet.weather_forecast_samples[..packet.weather_forecast_samples.len().min(56)];
+    buf.put_u8(weather_forecast_samples.len() as u8);
+    write_padded(
+        buf,
+        weather_forecast_samples,
+        56,
+        8,
+        write_weather_forecast_sample,
+    );
+    buf.put_u8(match packet.forecast_accuracy {
+        ForecastAccuracy::Perfect => 0,
+        ForecastAccuracy::Approximate => 1,
+    });
+    buf.put_u8(packet.ai_difficulty);
+    buf.put_u32_le(packet.season_link_identifier);
+    buf.put_u32_le(packet.weekend_link_identifier);
+    buf.put_u32_le(packet.session_link_identifier);
+    buf.put_u8(packet.pit_stop_window_ideal_lap);
+    buf.put_u8(packet.pit_stop_window_latest_lap);
+    buf.put_u8(packet.pit_stop_rejoin_position);
+    buf.put_u8(packet.steering_assist.into());
+    buf.put_u8(match packet.braking_assist {
+        BrakingAssist::Off => 0,
+        BrakingAssist::Low => 1,
+        BrakingAssist::Medium => 2,
+        BrakingAssist::High => 3,
+    });
+    buf.put_u8(match packet.gearbox_assist {
+        GearboxAssist::Unknown => 0,
+        GearboxAssist::Manual => 1,
+        GearboxAssist::ManualSuggested => 2,
+        GearboxAssist::Auto => 3,
+    });
+    buf.put_u8(packet.pit_assist.into());
+    buf.put_u8(packet.pit_release_assist.into());
+    buf.put_u8(packet.ers_assist.into());
+    buf.put_u8(packet.drs_assist.into());
+    buf.put_u8(match packet.dynamic_racing_line {
+        DynamicRacingLine::Off => 0,
+        DynamicRacingLine::Corners => 1,
+        DynamicRacingLine::Full => 2,
+    });
+    buf.put_u8(match packet.dynamic_racing_line_type {
+        DynamicRacingLineType::TwoD => 0,
+        DynamicRacingLineType::ThreeD => 1,
+    });
+    buf.put_u8(encode_game_mode(packet.game_mode));
+    buf.put_u8(encode_ruleset(packet.ruleset));
+    buf.put_u32_le(packet.time_of_day);
+    buf.put_u8(encode_session_length(packet.session_length));
+    buf.put_u8(encode_speed_unit(packet.speed_units_lead_player));
+    buf.put_u8(encode_temp_unit(packet.temperature_units_lead_player));
+    buf.put_u8(encode_speed_unit(packet.speed_units_secondary_player));
+    buf.put_u8(encode_temp_unit(packet.temperature_units_secondary_player));
+    buf.put_u8(packet.num_safety_car_periods.unwrap_or(0));
+    buf.put_u8(packet.num_virtual_safety_car_periods.unwrap_or(0));
+    buf.put_u8(packet.num_red_flag_periods.unwrap_or(0));
+}
+
+pub fn write_marshal_zone(buf: &mut BytesMut, marshal_zone: &MarshalZone) {
+    buf.put_f32_le(marshal_zone.zone_start);
+    buf.put_i8(encode_marshal_flag(marshal_zone.zone_flag));
+}
+
+pub fn write_weather_forecast_sample(buf: &mut BytesMut, sample: &WeatherForecastSample) {
+    buf.put_u8(encode_session_type(sample.session_type));
+    buf.put_u8(sample.time_offset);
+    buf.put_u8(encode_weather(sample.weather));
+    buf.put_i8(sample.track_temperature);
+    buf.put_i8(encode_temp_change(sample.track_temperature_change));
+    buf.put_i8(sample.air_temperature);
+    buf.put_i8(encode_temp_change(sample.air_temperature_change));
+    buf.put_u8(sample.rain_percentage);
+}
+
+fn encode_temp_change(temp_change: TempChange) -> i8 {
+    match temp_change {
+        TempChange::Increase => 0,
+        TempChange::Decrease => 1,
+        TempChange::NoChange => 2,
+    }
+}
+
+fn encode_speed_unit(speed_unit: Option<SpeedUnit>) -> u8 {
+    match speed_unit {
+        Some(SpeedUnit::Mph) | None => 0,
+        Some(SpeedUnit::Kmh) => 1,
+    }
+}
+
+fn encode_temp_unit(temp_unit: Option<TempUnit>) -> u8 {
+    match temp_unit {
+        Some(TempUnit::Celsius) | None => 0,
+        Some(TempUnit::Fahrenheit) => 1,
+    }
+}
+
+pub fn encode_track(track: Track) -> i8 {
+    match track {
+        Track::Unknown => -1,
+        Track::Melbourne => 0,
+        Track::PaulRicard => 1,
+        Track::Shanghai => 2,
+        Track::Sakhir => 3,
+        Track::Catalunya => 4,
+        Track::Monaco => 5,
+        Track::Montreal => 6,
+        Track::Silverstone => 7,
+        Track::Hockenheim => 8,
+        Track::Hungaroring => 9,
+        Track::Spa => 10,
+        Track::Monza => 11,
+        Track::Singapore => 12,
+        Track::Suzuka => 13,
+        Track::AbuDhabi => 14,
+        Track::Texas => 15,
+        Track::Brazil => 16,
+        Track::Austria => 17,
+        Track::Sochi => 18,
+        Track::Mexico => 19,
+        Track::Baku => 20,
+        Track::SakhirShort => 21,
+        Track::SilverstoneShort => 22,
+        Track::TexasShort => 23,
+        Track::SuzukaShort => 24,
+        Track::Hanoi => 25,
+        Track::Zandvoort => 26,
+        Track::Imola => 27,
+        Track::Portimao => 28,
+        Track::Jeddah => 29,
+        Track::Miami => 30,
+        Track::Vegas => 31,
+        Track::Losail => 32,
+    }
+}
+
+pub fn encode_marshal_flag(marshal_flag: MarshalFlag) -> i8 {
+    match marshal_flag {
+        MarshalFlag::None => 0,
+        MarshalFlag::Green => 1,
+        MarshalFlag::Blue => 2,
+        MarshalFlag::Yellow => 3,
+        MarshalFlag::Unknown => -1,
+    }
+}
+
+pub fn encode_session_type(session_type: SessionType) -> u8 {
+    match session_type {
+        SessionType::P1 => 1,
+        SessionType::P2 => 2,
+        SessionType::P3 => 3,
+        SessionType::ShortP => 4,
+        SessionType::Q1 => 5,
+        SessionType::Q2 => 6,
+        SessionType::Q3 => 7,
+        SessionType::ShortQ => 8,
+        SessionType::OSQ => 9,
+        SessionType::R => 10,
+        SessionType::R2 => 11,
+        SessionType::R3 => 12,
+        SessionType::TimeTrial => 13,
+        SessionType::Unknown
+        | SessionType::SprintShootout1
+        | SessionType::SprintShootout2
+        | SessionType::SprintShootout3
+        | SessionType::ShortSprintShootout
+        | SessionType::OneShotSprintShootout => 0,
+    }
+}
+
+pub fn encode_weather(weather: Weather) -> u8 {
+    match weather {
+        Weather::LightCloud => 1,
+        Weather::Overcast => 2,
+        Weather::LightRain => 3,
+        Weather::HeavyRain => 4,
+        Weather::Storm => 5,
+        Weather::Clear => 0,
+    }
+}
+
+pub fn encode_game_mode(game_mode: GameMode) -> u8 {
+    match game_mode {
+        GameMode::EventMode => 0,
+        GameMode::GrandPrix => 3,
+        GameMode::GrandPrix23 => 4,
+        GameMode::TimeTrial => 5,
+        GameMode::Splitscreen => 6,
+        GameMode::OnlineCustom => 7,
+        GameMode::OnlineLeague => 8,
+        GameMode::CareerInvitational => 11,
+        GameMode::ChampionshipInvitational => 12,
+        GameMode::Championship => 13,
+        GameMode::OnlineChampionship => 14,
+        GameMode::OnlineWeeklyEvent => 15,
+        GameMode::StoryMode => 17,
+        GameMode::Career22 => 19,
+        GameMode::Career22Online => 20,
+        GameMode::Career23 => 21,
+        GameMode::Career23Online => 22,
+        GameMode::Benchmark => 127,
+        GameMode::Unknown
+        | GameMode::MyTeamCareer24
+        | GameMode::DriverCareer24
+        | GameMode::Career24Online
+        | GameMode::ChallengeCareer24
+        | GameMode::StoryModeAPXGP => 255,
+    }
+}
+
+pub fn encode_ruleset(ruleset: Ruleset) -> u8 {
+    match ruleset {
+        Ruleset::PracticeAndQualifying => 0,
+        Ruleset::Race => 1,
+        Ruleset::TimeTrial => 2,
+        Ruleset::TimeAttack => 4,
+        Ruleset::CheckpointChallenge => 6,
+        Ruleset::Autocross => 8,
+        Ruleset::Drift => 9,
+        Ruleset::AverageSpeedZone => 10,
+        Ruleset::RivalDuel => 11,
+        Ruleset::Unknown | Ruleset::Elimination => 255,
+    }
+}
+
+pub fn encode_session_length(session_length: SessionLength) -> u8 {
+    match session_length {
+        SessionLength::None => 0,
+        SessionLength::VeryShort => 2,
+        SessionLength::Short => 3,
+        SessionLength::Medium => 4,
+        SessionLength::MediumLong => 5,
+        SessionLength::Long => 6,
+        SessionLength::Full => 7,
+    }
+}
+
+pub fn encode_safety_car_status(safety_car_status: SafetyCarStatus) -> u8 {
+    match safety_car_status {
+        SafetyCarStatus::No => 0,
+        SafetyCarStatus::Full => 1,
+        SafetyCarStatus::Virtual => 2,
+        SafetyCarStatus::Formation => 3,
+    }
+}
@@ -1,17 +1,23 @@
 use std::{io::Cursor, time::Duration};
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::packet::session_history::{LapHistoryData, SessionHistoryPacket, TyreStintHistoryData};
 
-use super::{car_status::{parse_tyre_compound_actual, parse_tyre_compound_visual}, header::parse_header};
+use super::{
+    car_status::{
+        encode_tyre_compound_actual, encode_tyre_compound_visual, parse_tyre_compound_actual,
+        parse_tyre_compound_visual,
+    },
+    header::{parse_header, write_header},
+    lap_data::write_split_time,
+    write_padded,
+};
 
 pub fn parse_session_history_packet(
     cursor: &mut Cursor<Bytes>,
 ) -> crate::Result<SessionHistoryPacket> {
-    if cursor.remaining() < 1155 {
-        return Err(crate::TelemetryError::InvalidPacket("packet too small".to_owned()));
-    }
+    crate::check_packet_length(cursor, "session history", 1460)?;
 
     let header = parse_header(cursor)?;
 
@@ -36,6 +42,7 @@ pub fn parse_session_history_packet(
 
     Ok(SessionHistoryPacket {
         header,
+        raw: None,
         car_index,
         best_lap_time_lap_num,
         best_sector_1_lap_num,
@@ -46,7 +53,7 @@ pub fn parse_session_history_packet(
     })
 }
 
-fn parse_lap_history_data(cursor: &mut Cursor<Bytes>) -> LapHistoryData {
+pub fn parse_lap_history_data(cursor: &mut Cursor<Bytes>) -> LapHistoryData {
     let lap_time_in_ms = cursor.get_u32_le();
     let sector_1_time_in_ms = cursor.get_u16_le();
     let sector_1_time_in_minutes = cursor.get_u8();
@@ -81,7 +88,7 @@ fn parse_lap_history_data(cursor: &mut Cursor<Bytes>) -> LapHistoryData {
     }
 }
 
-fn parse_tyre_stint_history_data(cursor: &mut Cursor<Bytes>) -> TyreStintHistoryData {
+pub fn parse_tyre_stint_history_data(cursor: &mut Cursor<Bytes>) -> TyreStintHistoryData {
     let end_lap = cursor.get_u8();
     let actual_tyre = parse_tyre_compound_actual(cursor.get_u8());
     let visual_tyre = parse_tyre_compound_visual(cursor.get_u8());
@@ -92,3 +99,55 @@ fn parse_tyre_stint_history_data(cursor: &mut Cursor<Bytes>) -> TyreStintHistory
         visual_tyre,
     }
 }
+
+pub fn write_session_history_packet(buf: &mut BytesMut, packet: &SessionHistoryPacket) {
+    write_header(buf, &packet.header);
+    buf.put_u8(packet.car_index);
+    buf.put_u8(packet.lap_history_data.len().min(100) as u8);
+    buf.put_u8(packet.tyre_stint_history_data.len().min(8) as u8);
+    buf.put_u8(packet.best_lap_time_lap_num);
+    buf.put_u8(packet.best_sector_1_lap_num);
+    buf.put_u8(packet.best_sector_2_lap_num);
+    buf.put_u8(packet.best_sector_3_lap_num);
+    write_padded(
+        buf,
+        &packet.lap_history_data,
+        100,
+        14,
+        write_lap_history_data,
+    );
+    write_padded(
+        buf,
+        &packet.tyre_stint_history_data,
+        8,
+        3,
+        write_tyre_stint_history_data,
+    );
+}
+
+pub fn write_lap_history_data(buf: &mut BytesMut, data: &LapHistoryData) {
+    buf.put_u32_le(data.lap_time_in_ms);
+    write_split_time(buf, data.sector_1_time);
+    write_split_time(buf, data.sector_2_time);
+    write_split_time(buf, data.sector_3_time);
+    let mut lap_valid_bit_flags = 0;
+    if data.lap_valid {
+        lap_valid_bit_flags |= 0x01;
+    }
+    if data.sector_1_valid {
+        lap_valid_bit_flags |= 0x02;
+    }
+    if data.sector_2_valid {
+        lap_valid_bit_flags |= 0x04;
+    }
+    if data.sector_3_valid {
+        lap_valid_bit_flags |= 0x08;
+    }
+    buf.put_u8(lap_valid_bit_flags);
+}
+
+pub fn write_tyre_stint_history_data(buf: &mut BytesMut, data: &TyreStintHistoryData) {
+    buf.put_u8(data.end_lap);
+    buf.put_u8(encode_tyre_compound_actual(data.actual_tyre));
+    buf.put_u8(encode_tyre_compound_visual(data.visual_tyre));
+}
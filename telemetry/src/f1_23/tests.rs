@@ -0,0 +1,687 @@
+//! Decodes packets laid out the way F1 23 sends them and checks that encoding them gives back
+//! the same bytes.
+//!
+//! The packets are put together field by field from the F1 23 specification, with arbitrary
+//! values. Every packet is checked twice: once with the ids the encoder writes and zeros where
+//! the game zero fills, which the model alone encodes back, and once with arbitrary bytes for
+//! the ids, padding and unused entries, which have to be taken from the decoded datagram.
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use super::encode_twentythree;
+use crate::{decode_packet, encode_packet, packet::Packet};
+
+const MOTION: u8 = 0;
+const SESSION: u8 = 1;
+const LAP_DATA: u8 = 2;
+const EVENT: u8 = 3;
+const PARTICIPANTS: u8 = 4;
+const CAR_SETUPS: u8 = 5;
+const CAR_TELEMETRY: u8 = 6;
+const CAR_STATUS: u8 = 7;
+const FINAL_CLASSIFICATION: u8 = 8;
+const LOBBY_INFO: u8 = 9;
+const CAR_DAMAGE: u8 = 10;
+const SESSION_HISTORY: u8 = 11;
+const TYRE_SETS: u8 = 12;
+const MOTION_EX: u8 = 13;
+
+const WEATHER: &[u8] = &[0, 1, 2, 3, 4, 5];
+const SESSION_TYPES: &[u8] = &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+const MARSHAL_FLAGS: &[u8] = &[0, 1, 2, 3, 0xff];
+const ACTUAL_COMPOUNDS: &[u8] = &[7, 8, 9, 11, 12, 13, 14, 16, 17, 18, 19, 20, 21];
+const VISUAL_COMPOUNDS: &[u8] = &[7, 8, 9, 16, 17, 18, 19];
+const TEAMS: &[u8] = &[
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 89, 90, 95, 97, 104, 115, 128, 130, 131, 132, 133, 134, 135, 136,
+    138, 139, 140, 255,
+];
+const PLATFORMS: &[u8] = &[1, 3, 4, 6, 255];
+const NAMES: &[&str] = &["Max", "Lewis", "Charles", "Lando", "Fernando", "Oscar"];
+
+/// Writes a packet, filling the fields with arbitrary values that are the same every run
+struct Fixture {
+    buf: BytesMut,
+    state: u32,
+    /// Whether what the model doesn't keep is filled with arbitrary bytes as well
+    arbitrary: bool,
+}
+
+impl Fixture {
+    fn new(packet_id: u8, arbitrary: bool) -> Self {
+        let mut fixture = Self {
+            buf: BytesMut::new(),
+            state: 0x2023_0000 | packet_id as u32,
+            arbitrary,
+        };
+        fixture.buf.put_u16_le(2023);
+        fixture
+            .value(23)
+            .value(1)
+            .value(5)
+            .value(1)
+            .value(packet_id);
+        fixture.buf.put_u64_le(0x1234_5678_9abc_def0);
+        fixture.f32().u32().u32().value(3).value(255);
+        fixture
+    }
+
+    fn next(&mut self) -> u32 {
+        // xorshift32
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+
+    fn value(&mut self, value: u8) -> &mut Self {
+        self.buf.put_u8(value);
+        self
+    }
+
+    /// Bytes the game zero fills
+    fn zeros(&mut self, count: usize) -> &mut Self {
+        if self.arbitrary {
+            return self.times(count, |f| {
+                f.u8();
+            });
+        }
+        self.buf.put_bytes(0, count);
+        self
+    }
+
+    fn u8(&mut self) -> &mut Self {
+        let value = self.next() as u8;
+        self.value(value)
+    }
+
+    fn u16(&mut self) -> &mut Self {
+        let value = self.next() as u16;
+        self.buf.put_u16_le(value);
+        self
+    }
+
+    fn u32(&mut self) -> &mut Self {
+        let value = self.next();
+        self.buf.put_u32_le(value);
+        self
+    }
+
+    /// Multiples of 1/8 survive the conversions to and from durations exactly
+    fn f32(&mut self) -> &mut Self {
+        let value = (self.next() % 200_000) as f32 / 8.0 - 12_500.0;
+        self.buf.put_f32_le(value);
+        self
+    }
+
+    fn positive_f32(&mut self) -> &mut Self {
+        let value = (self.next() % 200_000) as f32 / 8.0;
+        self.buf.put_f32_le(value);
+        self
+    }
+
+    fn positive_f64(&mut self) -> &mut Self {
+        let value = (self.next() % 200_000) as f64 / 8.0;
+        self.buf.put_f64_le(value);
+        self
+    }
+
+    /// One of the ids the decoder knows, or any id
+    fn id(&mut self, ids: &[u8]) -> &mut Self {
+        if self.arbitrary {
+            return self.u8();
+        }
+        self.known(ids)
+    }
+
+    /// One of the ids the decoder knows, for ids it rejects the packet for otherwise
+    fn known(&mut self, ids: &[u8]) -> &mut Self {
+        let value = ids[self.next() as usize % ids.len()];
+        self.value(value)
+    }
+
+    fn bool(&mut self) -> &mut Self {
+        self.id(&[0, 1])
+    }
+
+    /// A time sent as its millisecond part and its minute part
+    fn split_time(&mut self) -> &mut Self {
+        let ms = (self.next() % 60_000) as u16;
+        self.buf.put_u16_le(ms);
+        let minutes = (self.next() % 3) as u8;
+        self.value(minutes)
+    }
+
+    fn name(&mut self) -> &mut Self {
+        let name = NAMES[self.next() as usize % NAMES.len()];
+        self.buf.put_slice(name.as_bytes());
+        if self.arbitrary {
+            // A non-breaking space, which is decoded as a space, and whatever follows the end
+            self.buf.put_slice("\u{a0}".as_bytes());
+            return self.value(0).zeros(48 - name.len() - 3);
+        }
+        self.zeros(48 - name.len())
+    }
+
+    fn times(&mut self, count: usize, mut write: impl FnMut(&mut Self)) -> &mut Self {
+        for _ in 0..count {
+            write(self);
+        }
+        self
+    }
+
+    /// Decodes the packet, encodes it again and compares the bytes
+    fn check(&mut self) {
+        let bytes: Bytes = self.buf.split().freeze();
+        let packet = decode_packet(bytes.clone()).unwrap();
+
+        assert_same(&encode_packet(&packet).unwrap(), &bytes);
+        if !self.arbitrary {
+            assert_same(&encode_twentythree(&packet), &bytes);
+        }
+    }
+}
+
+fn assert_same(encoded: &[u8], bytes: &[u8]) {
+    assert_eq!(encoded.len(), bytes.len());
+    if let Some(offset) = (0..bytes.len()).find(|&i| encoded[i] != bytes[i]) {
+        panic!(
+            "byte {offset} is {} instead of {}",
+            encoded[offset], bytes[offset]
+        );
+    }
+}
+
+#[test]
+fn motion() {
+    for arbitrary in [false, true] {
+        Fixture::new(MOTION, arbitrary)
+            .times(22, |car| {
+                car.times(6, |f| {
+                    f.f32();
+                })
+                .times(6, |f| {
+                    f.u16();
+                })
+                .times(6, |f| {
+                    f.f32();
+                });
+            })
+            .check();
+    }
+}
+
+#[test]
+fn session() {
+    for arbitrary in [false, true] {
+        Fixture::new(SESSION, arbitrary)
+            .id(WEATHER)
+            .u8()
+            .u8()
+            .u8()
+            .u16()
+            .id(SESSION_TYPES)
+            .id(&(0..=32).collect::<Vec<_>>())
+            .id(&[0, 1, 2, 3, 4, 5, 6, 7])
+            .u16()
+            .u16()
+            .u8()
+            .bool()
+            .bool()
+            .u8()
+            .bool()
+            // Marshal zones
+            .value(17)
+            .times(17, |zone| {
+                zone.f32().id(MARSHAL_FLAGS);
+            })
+            .zeros(4 * 5)
+            .id(&[0, 1, 2, 3])
+            .bool()
+            // Weather forecast samples
+            .value(40)
+            .times(40, |sample| {
+                sample
+                    .id(SESSION_TYPES)
+                    .u8()
+                    .id(WEATHER)
+                    .u8()
+                    .id(&[0, 1, 2])
+                    .u8()
+                    .id(&[0, 1, 2])
+                    .u8();
+            })
+            .zeros(16 * 8)
+            .bool()
+            .u8()
+            .u32()
+            .u32()
+            .u32()
+            .u8()
+            .u8()
+            .u8()
+            .bool()
+            .id(&[0, 1, 2, 3])
+            .id(&[1, 2, 3])
+            .bool()
+            .bool()
+            .bool()
+            .bool()
+            .id(&[0, 1, 2])
+            .bool()
+            .id(&[
+                0, 3, 4, 5, 6, 7, 8, 11, 12, 13, 14, 15, 17, 19, 20, 21, 22, 127, 255,
+            ])
+            .id(&[0, 1, 2, 4, 6, 8, 9, 10, 11, 255])
+            .u32()
+            .id(&[0, 2, 3, 4, 5, 6, 7])
+            .bool()
+            .bool()
+            .bool()
+            .bool()
+            .u8()
+            .u8()
+            .u8()
+            .check();
+    }
+}
+
+#[test]
+fn lap_data() {
+    for arbitrary in [false, true] {
+        Fixture::new(LAP_DATA, arbitrary)
+            .times(20, |car| {
+                car.u32()
+                    .u32()
+                    .split_time()
+                    .split_time()
+                    .u16()
+                    .u16()
+                    .f32()
+                    .f32()
+                    .f32()
+                    .u8()
+                    .u8()
+                    .id(&[0, 1, 2])
+                    .u8()
+                    .id(&[0, 1, 2])
+                    .bool()
+                    .u8()
+                    .u8()
+                    .u8()
+                    .u8()
+                    .u8()
+                    .u8()
+                    .id(&[0, 1, 2, 3, 4])
+                    // Cars that are inactive have all of their lap data zeroed
+                    .id(&[2, 3, 4, 5, 6, 7])
+                    .bool()
+                    .u16()
+                    .u16()
+                    .bool();
+            })
+            .zeros(2 * 50)
+            .u8()
+            .u8()
+            .check();
+    }
+}
+
+#[test]
+fn events() {
+    for arbitrary in [false, true] {
+        let event = |code: &[u8; 4], details: &dyn Fn(&mut Fixture)| {
+            let mut fixture = Fixture::new(EVENT, arbitrary);
+            fixture.buf.put_slice(code);
+            details(&mut fixture);
+            let padding = 45 - fixture.buf.len();
+            fixture.zeros(padding).check();
+        };
+
+        for code in [
+            b"SSTA", b"SEND", b"DRSE", b"DRSD", b"CHQF", b"LGOT", b"SGSV", b"RDFL",
+        ] {
+            event(code, &|_| {});
+        }
+        for code in [b"RTMT", b"TMPT", b"RCWN", b"DTSV", b"STLG"] {
+            event(code, &|f| {
+                f.u8();
+            });
+        }
+        event(b"FTLP", &|f| {
+            f.u8().positive_f32();
+        });
+        event(b"PENA", &|f| {
+            f.id(&(0..=17).collect::<Vec<_>>())
+                .id(&(0..=54).collect::<Vec<_>>())
+                .u8()
+                .u8()
+                .u8()
+                .u8()
+                .u8();
+        });
+        event(b"SPTP", &|f| {
+            f.u8().f32().bool().bool().u8().f32();
+        });
+        event(b"FLBK", &|f| {
+            f.u32().f32();
+        });
+        event(b"BUTN", &|f| {
+            f.u32();
+        });
+        event(b"OVTK", &|f| {
+            f.u8().u8();
+        });
+        event(b"SCAR", &|f| {
+            f.id(&[0, 1, 2, 3]).id(&[0, 1, 2, 3, 255]);
+        });
+        event(b"COLL", &|f| {
+            f.u8().u8();
+        });
+        // Events the decoder doesn't know keep everything after their code
+        event(b"ABCD", &|f| {
+            f.times(12, |f| {
+                f.u8();
+            });
+        });
+    }
+}
+
+fn participants_packet(arbitrary: bool) -> Fixture {
+    let nationalities = (0..=87)
+        .filter(|id| ![24, 56, 70, 86].contains(id))
+        .collect::<Vec<_>>();
+    let mut fixture = Fixture::new(PARTICIPANTS, arbitrary);
+    fixture.value(20).times(22, |car| {
+        car.bool()
+            .u8()
+            .u8()
+            .id(TEAMS)
+            .bool()
+            .u8()
+            .id(&nationalities)
+            .name()
+            .bool()
+            .bool()
+            .id(PLATFORMS);
+    });
+    fixture
+}
+
+#[test]
+fn participants() {
+    for arbitrary in [false, true] {
+        participants_packet(arbitrary).check();
+    }
+}
+
+/// Renaming a driver, like anonymising a recording does, leaves the rest of the packet alone
+#[test]
+fn changed_fields_are_encoded() {
+    let bytes = participants_packet(true).buf.freeze();
+    let Packet::Participants(mut packet) = decode_packet(bytes.clone()).unwrap() else {
+        panic!("not a participants packet");
+    };
+    packet.participants[4].name = "Driver 5".to_owned();
+
+    let encoded = encode_packet(&Packet::Participants(packet)).unwrap();
+    let name = 30 + 4 * 58 + 7;
+    assert_eq!(&encoded[name..name + 9], b"Driver 5\0");
+    assert_same(&encoded[..name], &bytes[..name]);
+    assert_same(&encoded[name + 48..], &bytes[name + 48..]);
+}
+
+#[test]
+fn other_games_are_rejected() {
+    let mut fixture = Fixture::new(EVENT, false);
+    fixture.buf[0..2].copy_from_slice(&2024_u16.to_le_bytes());
+    fixture.buf.put_slice(b"SSTA");
+    fixture.zeros(12);
+    let packet = decode_packet(fixture.buf.freeze()).unwrap();
+
+    assert!(encode_packet(&packet).is_err());
+}
+
+#[test]
+fn car_setups() {
+    for arbitrary in [false, true] {
+        Fixture::new(CAR_SETUPS, arbitrary)
+            .times(22, |car| {
+                car.u8()
+                    .u8()
+                    .u8()
+                    .u8()
+                    .f32()
+                    .f32()
+                    .f32()
+                    .f32()
+                    .times(8, |f| {
+                        f.u8();
+                    })
+                    .times(4, |f| {
+                        f.f32();
+                    })
+                    .u8()
+                    .f32();
+            })
+            .check();
+    }
+}
+
+#[test]
+fn car_telemetry() {
+    for arbitrary in [false, true] {
+        Fixture::new(CAR_TELEMETRY, arbitrary)
+            .times(22, |car| {
+                car.u16()
+                    .f32()
+                    .f32()
+                    .f32()
+                    .u8()
+                    .u8()
+                    .u16()
+                    .bool()
+                    .u8()
+                    .u16()
+                    .times(4, |f| {
+                        f.u16();
+                    })
+                    .times(8, |f| {
+                        f.u8();
+                    })
+                    .u16()
+                    .times(4, |f| {
+                        f.f32();
+                    })
+                    .times(4, |f| {
+                        f.id(&(0..=11).collect::<Vec<_>>());
+                    });
+            })
+            .id(&[0, 1, 2, 3, 4, 255])
+            .id(&[0, 1, 2, 3, 4, 255])
+            .u8()
+            .check();
+    }
+}
+
+#[test]
+fn car_status() {
+    for arbitrary in [false, true] {
+        Fixture::new(CAR_STATUS, arbitrary)
+            .times(22, |car| {
+                car.id(&[0, 1, 2])
+                    .bool()
+                    .id(&[0, 1, 2, 3])
+                    .u8()
+                    .bool()
+                    .f32()
+                    .f32()
+                    .f32()
+                    .u16()
+                    .u16()
+                    .u8()
+                    .u8()
+                    .u16()
+                    .id(ACTUAL_COMPOUNDS)
+                    .id(VISUAL_COMPOUNDS)
+                    .u8()
+                    .id(MARSHAL_FLAGS)
+                    .f32()
+                    .f32()
+                    .f32()
+                    .id(&[0, 1, 2, 3])
+                    .f32()
+                    .f32()
+                    .f32()
+                    .bool();
+            })
+            .check();
+    }
+}
+
+#[test]
+fn final_classification() {
+    for arbitrary in [false, true] {
+        Fixture::new(FINAL_CLASSIFICATION, arbitrary)
+            .value(19)
+            .times(19, |car| {
+                car.u8()
+                    .u8()
+                    .u8()
+                    .u8()
+                    .u8()
+                    // Cars that aren't classified have all of their data zeroed
+                    .id(&[2, 3, 4, 5, 7])
+                    .u32()
+                    .positive_f64()
+                    .u8()
+                    .u8()
+                    .value(3)
+                    .times(3, |f| {
+                        f.id(ACTUAL_COMPOUNDS);
+                    })
+                    .zeros(5)
+                    .times(3, |f| {
+                        f.id(VISUAL_COMPOUNDS);
+                    })
+                    .zeros(5)
+                    .times(3, |f| {
+                        f.u8();
+                    })
+                    .zeros(5);
+            })
+            // Cars that aren't classified are zero filled, though their race time and count of
+            // tyre stints still have to be valid
+            .times(3, |car| {
+                car.zeros(10)
+                    .times(8, |f| {
+                        f.value(0);
+                    })
+                    .zeros(2)
+                    .value(0)
+                    .zeros(24);
+            })
+            .check();
+    }
+}
+
+#[test]
+fn lobby_info() {
+    for arbitrary in [false, true] {
+        Fixture::new(LOBBY_INFO, arbitrary)
+            .value(12)
+            .times(12, |player| {
+                player
+                    .bool()
+                    .id(TEAMS)
+                    .u8()
+                    .id(PLATFORMS)
+                    .name()
+                    .u8()
+                    .known(&[0, 1, 2]);
+            })
+            .zeros(10 * 54)
+            .check();
+    }
+}
+
+#[test]
+fn car_damage() {
+    for arbitrary in [false, true] {
+        Fixture::new(CAR_DAMAGE, arbitrary)
+            .times(22, |car| {
+                car.times(4, |f| {
+                    f.f32();
+                })
+                .times(14, |f| {
+                    f.u8();
+                })
+                .bool()
+                .bool()
+                .times(8, |f| {
+                    f.u8();
+                })
+                .bool()
+                .bool();
+            })
+            .check();
+    }
+}
+
+#[test]
+fn session_history() {
+    for arbitrary in [false, true] {
+        Fixture::new(SESSION_HISTORY, arbitrary)
+            .u8()
+            .value(37)
+            .value(3)
+            .u8()
+            .u8()
+            .u8()
+            .u8()
+            .times(37, |lap| {
+                lap.u32()
+                    .split_time()
+                    .split_time()
+                    .split_time()
+                    .id(&(0..=15).collect::<Vec<_>>());
+            })
+            .zeros(63 * 14)
+            .times(3, |stint| {
+                stint.u8().id(ACTUAL_COMPOUNDS).id(VISUAL_COMPOUNDS);
+            })
+            .zeros(5 * 3)
+            .check();
+    }
+}
+
+#[test]
+fn tyre_sets() {
+    for arbitrary in [false, true] {
+        Fixture::new(TYRE_SETS, arbitrary)
+            .u8()
+            .times(20, |set| {
+                set.id(ACTUAL_COMPOUNDS)
+                    .id(VISUAL_COMPOUNDS)
+                    .u8()
+                    .bool()
+                    .id(SESSION_TYPES)
+                    .u8()
+                    .u8()
+                    .u16()
+                    .bool();
+            })
+            .u8()
+            .check();
+    }
+}
+
+#[test]
+fn motion_ex() {
+    for arbitrary in [false, true] {
+        Fixture::new(MOTION_EX, arbitrary)
+            .times(8 * 4 + 11 + 4, |f| {
+                f.f32();
+            })
+            .check();
+    }
+}
@@ -0,0 +1,76 @@
+use std::io::Cursor;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::packet::tyre_sets::{TyreSetData, TyreSetsPacket};
+
+use super::{
+    car_status::{
+        encode_tyre_compound_actual, encode_tyre_compound_visual, parse_tyre_compound_actual,
+        parse_tyre_compound_visual,
+    },
+    header::{parse_header, write_header},
+    session::{encode_session_type, parse_session_type},
+    write_padded,
+};
+
+pub fn parse_tyre_sets_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<TyreSetsPacket> {
+    crate::check_packet_length(cursor, "tyre sets", 231)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_index = cursor.get_u8();
+    let tyre_set_data = (0..20).map(|_| parse_tyre_set_data(cursor)).collect();
+    let fitted_index = cursor.get_u8();
+
+    Ok(TyreSetsPacket {
+        header,
+        raw: None,
+        car_index,
+        tyre_set_data,
+        fitted_index,
+    })
+}
+
+fn parse_tyre_set_data(cursor: &mut Cursor<Bytes>) -> TyreSetData {
+    let actual_tyre_compound = parse_tyre_compound_actual(cursor.get_u8());
+    let visual_tyre_compound = parse_tyre_compound_visual(cursor.get_u8());
+    let wear = cursor.get_u8();
+    let available = cursor.get_u8() != 0;
+    let recommended_session = parse_session_type(cursor);
+    let life_span = cursor.get_u8();
+    let usable_life = cursor.get_u8();
+    let lap_delta_time_in_ms = cursor.get_i16_le();
+    let fitted = cursor.get_u8() != 0;
+
+    TyreSetData {
+        actual_tyre_compound,
+        visual_tyre_compound,
+        wear,
+        available,
+        recommended_session,
+        life_span,
+        usable_life,
+        lap_delta_time_in_ms,
+        fitted,
+    }
+}
+
+pub fn write_tyre_sets_packet(buf: &mut BytesMut, packet: &TyreSetsPacket) {
+    write_header(buf, &packet.header);
+    buf.put_u8(packet.car_index);
+    write_padded(buf, &packet.tyre_set_data, 20, 10, write_tyre_set_data);
+    buf.put_u8(packet.fitted_index);
+}
+
+fn write_tyre_set_data(buf: &mut BytesMut, data: &TyreSetData) {
+    buf.put_u8(encode_tyre_compound_actual(data.actual_tyre_compound));
+    buf.put_u8(encode_tyre_compound_visual(data.visual_tyre_compound));
+    buf.put_u8(data.wear);
+    buf.put_u8(data.available.into());
+    buf.put_u8(encode_session_type(data.recommended_session));
+    buf.put_u8(data.life_span);
+    buf.put_u8(data.usable_life);
+    buf.put_i16_le(data.lap_delta_time_in_ms);
+    buf.put_u8(data.fitted.into());
+}
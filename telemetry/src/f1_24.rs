@@ -0,0 +1,57 @@
+use std::io::Cursor;
+
+use bytes::Bytes;
+
+use crate::packet::header::Header;
+
+use super::packet::{header::PacketId, Packet};
+
+mod car_damage;
+mod car_setups;
+mod car_status;
+mod car_telemetry;
+mod event;
+mod final_classification;
+mod header;
+mod lap_data;
+mod lobby_info;
+mod motion;
+mod motion_ex;
+mod participants;
+mod session;
+mod session_history;
+mod tyre_sets;
+
+pub fn decode_twentyfour(cursor: &mut Cursor<Bytes>) -> crate::Result<Packet> {
+    let packet_id = header::parse_header(cursor)?.packet_id;
+    cursor.set_position(0);
+
+    match packet_id {
+        PacketId::Motion => Ok(Packet::Motion(motion::parse_motion_packet(cursor)?)),
+        PacketId::Session => Ok(Packet::Session(session::parse_session_packet(cursor)?)),
+        PacketId::LapData => Ok(Packet::LapData(lap_data::parse_lap_data_packet(cursor)?)),
+        PacketId::Event => Ok(Packet::Event(event::parse_event_packet(cursor)?)),
+        PacketId::Participants => Ok(Packet::Participants(
+            participants::parse_participants_packet(cursor)?,
+        )),
+        PacketId::CarSetups => Ok(Packet::CarSetups(car_setups::parse_car_setups_packet(cursor)?)),
+        PacketId::CarTelemetry => Ok(Packet::CarTelemetry(car_telemetry::parse_car_telemetry(
+            cursor,
+        )?)),
+        PacketId::CarStatus => Ok(Packet::CarStatus(car_status::parse_car_status(cursor)?)),
+        PacketId::FinalClassification => Ok(Packet::FinalClassification(
+            final_classification::parse_final_classification_packet(cursor)?,
+        )),
+        PacketId::LobbyInfo => Ok(Packet::LobbyInfo(lobby_info::parse_lobby_info(cursor)?)),
+        PacketId::CarDamage => Ok(Packet::CarDamage(car_damage::parse_car_damage_packet(cursor)?)),
+        PacketId::SessionHistory => Ok(Packet::SessionHistory(
+            session_history::parse_session_history_packet(cursor)?,
+        )),
+        PacketId::MotionEx => Ok(Packet::MotionEx(motion_ex::parse_motion_ex_packet(cursor)?)),
+        PacketId::TyreSets => Ok(Packet::TyreSets(tyre_sets::parse_tyre_sets_packet(cursor)?)),
+    }
+}
+
+pub fn decode_twentyfour_header(cursor: &mut Cursor<Bytes>) -> crate::Result<Header> {
+    header::parse_header(cursor)
+}
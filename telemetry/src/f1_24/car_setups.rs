@@ -0,0 +1,75 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::packet::car_setups::{CarSetupData, CarSetupsPacket};
+
+use super::header::parse_header;
+
+pub fn parse_car_setups_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<CarSetupsPacket> {
+    crate::check_packet_length(cursor, "car setups", 1133)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_setups = (0..22).map(|_| parse_car_setup_data(cursor)).collect();
+
+    let next_front_wing_value = cursor.get_f32_le();
+
+    Ok(CarSetupsPacket {
+        header,
+        raw: None,
+        car_setups,
+        next_front_wing_value: Some(next_front_wing_value),
+    })
+}
+
+fn parse_car_setup_data(cursor: &mut Cursor<Bytes>) -> CarSetupData {
+    let front_wing = cursor.get_u8();
+    let rear_wing = cursor.get_u8();
+    let on_throttle = cursor.get_u8();
+    let off_throttle = cursor.get_u8();
+    let front_camber = cursor.get_f32_le();
+    let rear_camber = cursor.get_f32_le();
+    let front_toe = cursor.get_f32_le();
+    let rear_toe = cursor.get_f32_le();
+    let front_suspension = cursor.get_u8();
+    let rear_suspension = cursor.get_u8();
+    let front_anti_roll_bar = cursor.get_u8();
+    let rear_anti_roll_bar = cursor.get_u8();
+    let front_suspension_height = cursor.get_u8();
+    let rear_suspension_height = cursor.get_u8();
+    let brake_pressure = cursor.get_u8();
+    let brake_bias = cursor.get_u8();
+    let engine_braking = cursor.get_u8();
+    let tyres_pressure = [
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+        cursor.get_f32_le(),
+    ];
+    let ballast = cursor.get_u8();
+    let fuel_load = cursor.get_f32_le();
+
+    CarSetupData {
+        front_wing,
+        rear_wing,
+        on_throttle,
+        off_throttle,
+        front_camber,
+        rear_camber,
+        front_toe,
+        rear_toe,
+        front_suspension,
+        rear_suspension,
+        front_anti_roll_bar,
+        rear_anti_roll_bar,
+        front_suspension_height,
+        rear_suspension_height,
+        brake_pressure,
+        brake_bias,
+        engine_braking: Some(engine_braking),
+        tyres_pressure,
+        ballast,
+        fuel_load,
+    }
+}
@@ -0,0 +1,19 @@
+use std::io::Cursor;
+
+use bytes::Bytes;
+
+use crate::{f1_23::car_status::parse_car_status_data, packet::car_status::CarStatusPacket};
+
+use super::header::parse_header;
+
+pub fn parse_car_status(cursor: &mut Cursor<Bytes>) -> crate::Result<CarStatusPacket> {
+    crate::check_packet_length(cursor, "car status", 1239)?;
+    let header = parse_header(cursor)?;
+    let car_status_data: Vec<_> = (0..22).map(|_| parse_car_status_data(cursor)).collect();
+
+    Ok(CarStatusPacket {
+        header,
+        raw: None,
+        car_status_data,
+    })
+}
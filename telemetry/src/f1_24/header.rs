@@ -0,0 +1,49 @@
+use bytes::{Buf, Bytes};
+use std::io::Cursor;
+
+use crate::{
+    f1_23::header::parse_packet_id,
+    packet::header::{Format, GameVersion, Header},
+};
+
+const HEADER_SIZE: usize = 29;
+
+pub fn parse_header(cursor: &mut Cursor<Bytes>) -> crate::Result<Header> {
+    if cursor.remaining() < HEADER_SIZE {
+        return Err(crate::TelemetryError::InvalidPacket("packet too small".to_owned()));
+    }
+
+    let packet_format = cursor.get_u16_le();
+    if packet_format != 2024 {
+        return Err(crate::TelemetryError::InvalidPacket("expected packet format to be 2024".to_owned()));
+    }
+    let game_year = cursor.get_u8();
+    let game_version = GameVersion(cursor.get_u8(), cursor.get_u8());
+    let packet_version = cursor.get_u8();
+    let packet_id = parse_packet_id(cursor)?;
+    let session_uid = cursor.get_u64_le();
+    let session_time = cursor.get_f32_le();
+    let frame_identifier = cursor.get_u32_le();
+    let overall_frame_identifier = cursor.get_u32_le();
+    let player_car_index = cursor.get_u8();
+    let secondary_player_car_index = match cursor.get_u8() {
+        255 => None,
+        x => Some(x),
+    };
+
+    let header = Header {
+        format: Format::TwentyFour,
+        game_year,
+        game_version,
+        packet_version,
+        packet_id,
+        session_uid,
+        session_time,
+        frame_identifier,
+        overall_frame_identifier: Some(overall_frame_identifier),
+        player_car_index,
+        secondary_player_car_index,
+    };
+
+    Ok(header)
+}
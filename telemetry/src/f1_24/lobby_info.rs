@@ -0,0 +1,73 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::participants::{parse_name, parse_platform},
+    packet::{
+        lobby_info::{LobbyInfoData, LobbyInfoPacket, Status},
+        participants::Telemetry,
+    },
+    Result,
+};
+
+use super::{
+    header::parse_header,
+    participants::parse_team,
+};
+
+pub fn parse_lobby_info(cursor: &mut Cursor<Bytes>) -> Result<LobbyInfoPacket> {
+    crate::check_packet_length(cursor, "lobby info", 1306)?;
+
+    let header = parse_header(cursor)?;
+    let num_players = cursor.get_u8();
+    if num_players > 22 {
+        return Err(crate::TelemetryError::InvalidPacket(format!(
+            "invalid number of players in lobby info packet: {num_players}"
+        )));
+    }
+    let lobby_players = (0..num_players)
+        .map(|_| parse_lobby_info_data(cursor))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(LobbyInfoPacket {
+        header,
+        raw: None,
+        lobby_players,
+    })
+}
+
+fn parse_lobby_info_data(cursor: &mut Cursor<Bytes>) -> Result<LobbyInfoData> {
+    let ai_controlled: bool = cursor.get_u8() != 0;
+    let team = parse_team(cursor.get_u8());
+    let nationality = cursor.get_u8();
+    let platform = parse_platform(cursor.get_u8());
+    let name = parse_name(cursor);
+    let car_number = cursor.get_u8();
+    let your_telemetry = if cursor.get_u8() == 1 {
+        Telemetry::Public
+    } else {
+        Telemetry::Restricted
+    };
+    let show_online_names = cursor.get_u8() != 0;
+    let tech_level = cursor.get_u16_le();
+    let ready_status = match cursor.get_u8() {
+        0 => Status::NotReady,
+        1 => Status::Ready,
+        2 => Status::Spectating,
+        _ => return Err(crate::TelemetryError::InvalidPacket("invalid ready_status".to_owned())),
+    };
+
+    Ok(LobbyInfoData {
+        ai_controlled,
+        team,
+        nationality,
+        platform,
+        name,
+        car_number,
+        your_telemetry: Some(your_telemetry),
+        show_online_names: Some(show_online_names),
+        tech_level: Some(tech_level),
+        ready_status,
+    })
+}
@@ -0,0 +1,19 @@
+use crate::{f1_23::motion::parse_car_motion_data, packet::motion::MotionPacket};
+use bytes::Bytes;
+use std::io::Cursor;
+
+use super::header::parse_header;
+
+pub fn parse_motion_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<MotionPacket> {
+    crate::check_packet_length(cursor, "motion", 1349)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_motion_data = (0..22).map(|_| parse_car_motion_data(cursor)).collect();
+
+    Ok(MotionPacket {
+        header,
+        raw: None,
+        car_motion_data,
+    })
+}
@@ -0,0 +1,22 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{f1_23::motion_ex::parse_motion_ex_data, packet::motion_ex::MotionExPacket};
+
+use super::header::parse_header;
+
+pub fn parse_motion_ex_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<MotionExPacket> {
+    crate::check_packet_length(cursor, "motion ex", 237)?;
+
+    let header = parse_header(cursor)?;
+
+    let mut packet = parse_motion_ex_data(cursor, header);
+    packet.front_aero_height = Some(cursor.get_f32_le());
+    packet.rear_aero_height = Some(cursor.get_f32_le());
+    packet.front_roll_angle = Some(cursor.get_f32_le());
+    packet.rear_roll_angle = Some(cursor.get_f32_le());
+    packet.chassis_yaw = Some(cursor.get_f32_le());
+
+    Ok(packet)
+}
@@ -0,0 +1,88 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::participants::{parse_name, parse_nationality, parse_platform},
+    packet::participants::{ParticipantData, ParticipantsPacket, Team, Telemetry},
+};
+
+use super::header::parse_header;
+
+pub fn parse_participants_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<ParticipantsPacket> {
+    crate::check_packet_length(cursor, "participants", 1350)?;
+    let header = parse_header(cursor)?;
+
+    let num_active_cars = cursor.get_u8();
+    let participants = (0..22).map(|_| parse_participants_data(cursor)).collect();
+
+    Ok(ParticipantsPacket {
+        header,
+        raw: None,
+        num_active_cars,
+        participants,
+    })
+}
+
+fn parse_participants_data(cursor: &mut Cursor<Bytes>) -> ParticipantData {
+    let ai_controlled = cursor.get_u8() != 0;
+    let driver_id = cursor.get_u8();
+    let network_id = cursor.get_u8();
+    let team = parse_team(cursor.get_u8());
+    let my_team = cursor.get_u8() != 0;
+    let race_number = cursor.get_u8();
+    let nationality = parse_nationality(cursor.get_u8());
+    let name = parse_name(cursor);
+    let your_telemetry = if cursor.get_u8() == 1 {
+        Telemetry::Public
+    } else {
+        Telemetry::Restricted
+    };
+    let show_online_names = cursor.get_u8() != 0;
+    let tech_level = cursor.get_u16_le();
+    let platform = parse_platform(cursor.get_u8());
+
+    ParticipantData {
+        ai_controlled,
+        driver_id,
+        network_id,
+        team,
+        my_team,
+        race_number,
+        nationality,
+        name,
+        your_telemetry,
+        show_online_names: Some(show_online_names),
+        tech_level: Some(tech_level),
+        platform,
+    }
+}
+
+pub fn parse_team(team_id: u8) -> Team {
+    match team_id {
+        0 => Team::Mercedes,
+        1 => Team::Ferrari,
+        2 => Team::RedBullRacing,
+        3 => Team::Williams,
+        4 => Team::AstonMartin,
+        5 => Team::Alpine,
+        6 => Team::RB,
+        7 => Team::Haas,
+        8 => Team::McLaren,
+        9 => Team::Sauber,
+        41 => Team::F1Generic,
+        104 => Team::CustomTeam,
+        143 => Team::ArtGP,
+        144 => Team::Campos,
+        145 => Team::Carlin,
+        146 => Team::PHM,
+        147 => Team::Dams,
+        148 => Team::Hitech,
+        149 => Team::MPMotorsport,
+        150 => Team::Prema,
+        151 => Team::Trident,
+        152 => Team::VanAmersfoortRacing,
+        153 => Team::Virtuosi,
+        _ => Team::Unknown,
+    }
+}
@@ -0,0 +1,389 @@
+use bytes::{Buf, Bytes};
+
+use std::io::Cursor;
+
+use crate::{
+    f1_23::session::{parse_marshal_zone, parse_session_length, parse_track, parse_weather},
+    packet::session::{
+        BrakingAssist, CarDamage, CarDamageRate, Collisions, Difficulty, DynamicRacingLine,
+        DynamicRacingLineType, Experience, FlashbackLimit, ForecastAccuracy, Formula, Frequency,
+        GameMode, GearboxAssist, MarshalZone, RecoveryMode, Ruleset, SafetyCarStatus,
+        SessionPacket, SessionRules, SessionType, SpeedUnit, TempChange, TempUnit,
+        WeatherForecastSample,
+    },
+};
+
+use super::header::parse_header;
+
+pub fn parse_session_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<SessionPacket> {
+    crate::check_packet_length(cursor, "session", 753)?;
+
+    let header = parse_header(cursor)?;
+
+    let weather = parse_weather(cursor);
+    let track_temperature = cursor.get_i8();
+    let air_temperature = cursor.get_i8();
+    let total_laps = cursor.get_u8();
+    let track_length = cursor.get_u16_le();
+    let session_type = parse_session_type(cursor.get_u8());
+    let track = parse_track(cursor);
+    let formula = match cursor.get_u8() {
+        1 => Formula::F1Classic,
+        2 => Formula::F2,
+        3 => Formula::F1Generic,
+        4 => Formula::Beta,
+        6 => Formula::Esports,
+        8 => Formula::F1World,
+        9 => Formula::F1Elimination,
+        _ => Formula::F1Modern,
+    };
+    let session_time_left = cursor.get_u16_le();
+    let session_duration = cursor.get_u16_le();
+    let pit_speed_limit = cursor.get_u8();
+    let game_paused = cursor.get_u8() != 0;
+    let is_spectating = cursor.get_u8() != 0;
+    let spectator_car_index = cursor.get_u8();
+    let sli_pro_native_support = cursor.get_u8() != 0;
+    let num_marshal_zones = cursor.get_u8();
+    let mut marshal_zones: Vec<MarshalZone> = (0..21).map(|_| parse_marshal_zone(cursor)).collect();
+    marshal_zones.truncate(num_marshal_zones as usize);
+    let marshal_zones = marshal_zones;
+    let safety_car_status = match cursor.get_u8() {
+        1 => SafetyCarStatus::Full,
+        2 => SafetyCarStatus::Virtual,
+        3 => SafetyCarStatus::Formation,
+        _ => SafetyCarStatus::No,
+    };
+    let network_game = cursor.get_u8() != 0;
+    let num_weather_forecast_samples = cursor.get_u8();
+    let mut weather_forecast_samples: Vec<WeatherForecastSample> = (0..64)
+        .map(|_| parse_weather_forecast_sample(cursor))
+        .collect();
+    weather_forecast_samples.truncate(num_weather_forecast_samples as usize);
+    let weather_forecast_samples = weather_forecast_samples;
+    let forecast_accuracy = if cursor.get_u8() == 0 {
+        ForecastAccuracy::Perfect
+    } else {
+        ForecastAccuracy::Approximate
+    };
+    let ai_difficulty: u8 = cursor.get_u8();
+    let season_link_identifier = cursor.get_u32_le();
+    let weekend_link_identifier = cursor.get_u32_le();
+    let session_link_identifier = cursor.get_u32_le();
+    let pit_stop_window_ideal_lap = cursor.get_u8();
+    let pit_stop_window_latest_lap = cursor.get_u8();
+    let pit_stop_rejoin_position = cursor.get_u8();
+    let steering_assist = cursor.get_u8() != 0;
+    let braking_assist = match cursor.get_u8() {
+        0 => BrakingAssist::Off,
+        1 => BrakingAssist::Low,
+        2 => BrakingAssist::Medium,
+        _ => BrakingAssist::High,
+    };
+    let gearbox_assist = match cursor.get_u8() {
+        1 => GearboxAssist::Manual,
+        2 => GearboxAssist::ManualSuggested,
+        _ => GearboxAssist::Auto,
+    };
+    let pit_assist = cursor.get_u8() != 0;
+    let pit_release_assist = cursor.get_u8() != 0;
+    let ers_assist = cursor.get_u8() != 0;
+    let drs_assist = cursor.get_u8() != 0;
+    let dynamic_racing_line = match cursor.get_u8() {
+        0 => DynamicRacingLine::Off,
+        1 => DynamicRacingLine::Corners,
+        _ => DynamicRacingLine::Full,
+    };
+    let dynamic_racing_line_type = if cursor.get_u8() == 0 {
+        DynamicRacingLineType::TwoD
+    } else {
+        DynamicRacingLineType::ThreeD
+    };
+
+    let game_mode = parse_game_mode(cursor);
+    let ruleset = parse_ruleset(cursor);
+    let time_of_day = cursor.get_u32_le();
+    let session_length = parse_session_length(cursor);
+
+    let speed_units_lead_player = if cursor.get_u8() == 0 {
+        SpeedUnit::Mph
+    } else {
+        SpeedUnit::Kmh
+    };
+    let temperature_units_lead_player = if cursor.get_u8() == 0 {
+        TempUnit::Celsius
+    } else {
+        TempUnit::Fahrenheit
+    };
+    let speed_units_secondary_player = if cursor.get_u8() == 0 {
+        SpeedUnit::Mph
+    } else {
+        SpeedUnit::Kmh
+    };
+    let temperature_units_secondary_player = if cursor.get_u8() == 0 {
+        TempUnit::Celsius
+    } else {
+        TempUnit::Fahrenheit
+    };
+    let num_safety_car_periods = cursor.get_u8();
+    let num_virtual_safety_car_periods = cursor.get_u8();
+    let num_red_flag_periods = cursor.get_u8();
+
+    let rules = parse_session_rules(cursor);
+
+    let num_sessions_in_weekend = cursor.get_u8();
+    let mut weekend_structure: Vec<SessionType> =
+        (0..12).map(|_| parse_session_type(cursor.get_u8())).collect();
+    weekend_structure.truncate(num_sessions_in_weekend as usize);
+    let weekend_structure = weekend_structure;
+    let sector_2_lap_distance_start = cursor.get_f32_le();
+    let sector_3_lap_distance_start = cursor.get_f32_le();
+
+    Ok(SessionPacket {
+        header,
+        raw: None,
+        weather,
+        track_temperature,
+        air_temperature,
+        total_laps,
+        track_length,
+        session_type,
+        track,
+        formula,
+        session_time_left,
+        session_duration,
+        pit_speed_limit,
+        game_paused,
+        is_spectating,
+        spectator_car_index,
+        sli_pro_native_support,
+        marshal_zones,
+        safety_car_status,
+        network_game,
+        weather_forecast_samples,
+        forecast_accuracy,
+        ai_difficulty,
+        season_link_identifier,
+        weekend_link_identifier,
+        session_link_identifier,
+        pit_stop_window_ideal_lap,
+        pit_stop_window_latest_lap,
+        pit_stop_rejoin_position,
+        steering_assist,
+        braking_assist,
+        gearbox_assist,
+        pit_assist,
+        pit_release_assist,
+        ers_assist,
+        drs_assist,
+        dynamic_racing_line,
+        dynamic_racing_line_type,
+        game_mode,
+        ruleset,
+        time_of_day,
+        session_length,
+        speed_units_lead_player: Some(speed_units_lead_player),
+        temperature_units_lead_player: Some(temperature_units_lead_player),
+        speed_units_secondary_player: Some(speed_units_secondary_player),
+        temperature_units_secondary_player: Some(temperature_units_secondary_player),
+        num_safety_car_periods: Some(num_safety_car_periods),
+        num_virtual_safety_car_periods: Some(num_virtual_safety_car_periods),
+        num_red_flag_periods: Some(num_red_flag_periods),
+        rules: Some(rules),
+        weekend_structure,
+        sector_2_lap_distance_start: Some(sector_2_lap_distance_start),
+        sector_3_lap_distance_start: Some(sector_3_lap_distance_start),
+    })
+}
+
+fn parse_session_rules(cursor: &mut Cursor<Bytes>) -> SessionRules {
+    let equal_car_performance = cursor.get_u8() != 0;
+    let recovery_mode = match cursor.get_u8() {
+        1 => RecoveryMode::Flashbacks,
+        2 => RecoveryMode::AutoRecovery,
+        _ => RecoveryMode::None,
+    };
+    let flashback_limit = match cursor.get_u8() {
+        0 => FlashbackLimit::Low,
+        1 => FlashbackLimit::Medium,
+        2 => FlashbackLimit::High,
+        _ => FlashbackLimit::Unlimited,
+    };
+    let realistic_surface_type = cursor.get_u8() != 0;
+    let low_fuel_mode = if cursor.get_u8() == 0 {
+        Difficulty::Easy
+    } else {
+        Difficulty::Hard
+    };
+    let assisted_race_starts = cursor.get_u8() != 0;
+    let carcass_tyre_temperature = cursor.get_u8() != 0;
+    let pit_lane_tyre_sim = cursor.get_u8() != 0;
+    let car_damage = match cursor.get_u8() {
+        0 => CarDamage::Off,
+        1 => CarDamage::Reduced,
+        2 => CarDamage::Standard,
+        _ => CarDamage::Simulation,
+    };
+    let car_damage_rate = match cursor.get_u8() {
+        0 => CarDamageRate::Reduced,
+        1 => CarDamageRate::Standard,
+        _ => CarDamageRate::Simulation,
+    };
+    let collisions = match cursor.get_u8() {
+        0 => Collisions::Off,
+        1 => Collisions::PlayerToPlayerOff,
+        _ => Collisions::On,
+    };
+    let collisions_off_for_first_lap_only = cursor.get_u8() != 0;
+    // 0 = On, 1 = Off
+    let mp_unsafe_pit_release = cursor.get_u8() == 0;
+    let mp_off_for_griefing = cursor.get_u8() != 0;
+    let strict_corner_cutting = cursor.get_u8() != 0;
+    let parc_ferme_rules = cursor.get_u8() != 0;
+    let pit_stop_experience = match cursor.get_u8() {
+        0 => Experience::Automatic,
+        1 => Experience::Broadcast,
+        _ => Experience::Immersive,
+    };
+    let safety_car = parse_frequency(cursor.get_u8());
+    let safety_car_experience = if cursor.get_u8() == 0 {
+        Experience::Broadcast
+    } else {
+        Experience::Immersive
+    };
+    let formation_lap = cursor.get_u8() != 0;
+    let formation_lap_experience = if cursor.get_u8() == 0 {
+        Experience::Broadcast
+    } else {
+        Experience::Immersive
+    };
+    let red_flags = parse_frequency(cursor.get_u8());
+    let affects_licence_level_solo = cursor.get_u8() != 0;
+    let affects_licence_level_mp = cursor.get_u8() != 0;
+
+    SessionRules {
+        equal_car_performance,
+        recovery_mode,
+        flashback_limit,
+        realistic_surface_type,
+        low_fuel_mode,
+        assisted_race_starts,
+        carcass_tyre_temperature,
+        pit_lane_tyre_sim,
+        car_damage,
+        car_damage_rate,
+        collisions,
+        collisions_off_for_first_lap_only,
+        mp_unsafe_pit_release,
+        mp_off_for_griefing,
+        strict_corner_cutting,
+        parc_ferme_rules,
+        pit_stop_experience,
+        safety_car,
+        safety_car_experience,
+        formation_lap,
+        formation_lap_experience,
+        red_flags,
+        affects_licence_level_solo,
+        affects_licence_level_mp,
+    }
+}
+
+fn parse_frequency(frequency: u8) -> Frequency {
+    match frequency {
+        0 => Frequency::Off,
+        1 => Frequency::Reduced,
+        2 => Frequency::Standard,
+        _ => Frequency::Increased,
+    }
+}
+
+pub fn parse_session_type(session_type: u8) -> SessionType {
+    match session_type {
+        1 => SessionType::P1,
+        2 => SessionType::P2,
+        3 => SessionType::P3,
+        4 => SessionType::ShortP,
+        5 => SessionType::Q1,
+        6 => SessionType::Q2,
+        7 => SessionType::Q3,
+        8 => SessionType::ShortQ,
+        9 => SessionType::OSQ,
+        10 => SessionType::SprintShootout1,
+        11 => SessionType::SprintShootout2,
+        12 => SessionType::SprintShootout3,
+        13 => SessionType::ShortSprintShootout,
+        14 => SessionType::OneShotSprintShootout,
+        15 => SessionType::R,
+        16 => SessionType::R2,
+        17 => SessionType::R3,
+        18 => SessionType::TimeTrial,
+        _ => SessionType::Unknown,
+    }
+}
+
+fn parse_temp_change(cursor: &mut Cursor<Bytes>) -> TempChange {
+    match cursor.get_i8() {
+        0 => TempChange::Increase,
+        1 => TempChange::Decrease,
+        _ => TempChange::NoChange,
+    }
+}
+
+fn parse_weather_forecast_sample(cursor: &mut Cursor<Bytes>) -> WeatherForecastSample {
+    let session_type = parse_session_type(cursor.get_u8());
+    let time_offset = cursor.get_u8();
+    let weather = parse_weather(cursor);
+    let track_temperature = cursor.get_i8();
+    let track_temperature_change = parse_temp_change(cursor);
+    let air_temperature = cursor.get_i8();
+    let air_temperature_change = parse_temp_change(cursor);
+    let rain_percentage = cursor.get_u8();
+
+    WeatherForecastSample {
+        session_type,
+        time_offset,
+        weather,
+        track_temperature,
+        track_temperature_change,
+        air_temperature,
+        air_temperature_change,
+        rain_percentage,
+    }
+}
+
+fn parse_game_mode(cursor: &mut Cursor<Bytes>) -> GameMode {
+    match cursor.get_u8() {
+        0 => GameMode::EventMode,
+        3 => GameMode::GrandPrix,
+        4 => GameMode::GrandPrix23,
+        5 => GameMode::TimeTrial,
+        6 => GameMode::Splitscreen,
+        7 => GameMode::OnlineCustom,
+        14 => GameMode::OnlineWeeklyEvent,
+        17 => GameMode::StoryMode,
+        27 => GameMode::MyTeamCareer24,
+        28 => GameMode::DriverCareer24,
+        29 => GameMode::Career24Online,
+        30 => GameMode::ChallengeCareer24,
+        75 => GameMode::StoryModeAPXGP,
+        127 => GameMode::Benchmark,
+        _ => GameMode::Unknown,
+    }
+}
+
+fn parse_ruleset(cursor: &mut Cursor<Bytes>) -> Ruleset {
+    match cursor.get_u8() {
+        0 => Ruleset::PracticeAndQualifying,
+        1 => Ruleset::Race,
+        2 => Ruleset::TimeTrial,
+        4 => Ruleset::TimeAttack,
+        6 => Ruleset::CheckpointChallenge,
+        8 => Ruleset::Autocross,
+        9 => Ruleset::Drift,
+        10 => Ruleset::AverageSpeedZone,
+        11 => Ruleset::RivalDuel,
+        12 => Ruleset::Elimination,
+        _ => Ruleset::Unknown,
+    }
+}
@@ -0,0 +1,49 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::session_history::{parse_lap_history_data, parse_tyre_stint_history_data},
+    packet::session_history::SessionHistoryPacket,
+};
+
+use super::header::parse_header;
+
+pub fn parse_session_history_packet(
+    cursor: &mut Cursor<Bytes>,
+) -> crate::Result<SessionHistoryPacket> {
+    crate::check_packet_length(cursor, "session history", 1460)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_index = cursor.get_u8();
+    let num_laps = cursor.get_u8();
+    let num_tyre_stints = cursor.get_u8();
+
+    let best_lap_time_lap_num = cursor.get_u8();
+    let best_sector_1_lap_num = cursor.get_u8();
+    let best_sector_2_lap_num = cursor.get_u8();
+    let best_sector_3_lap_num = cursor.get_u8();
+
+    let mut lap_history_data = (0..100)
+        .map(|_| parse_lap_history_data(cursor))
+        .collect::<Vec<_>>();
+    lap_history_data.truncate(num_laps as usize);
+
+    let mut tyre_stint_history_data = (0..8)
+        .map(|_| parse_tyre_stint_history_data(cursor))
+        .collect::<Vec<_>>();
+    tyre_stint_history_data.truncate(num_tyre_stints as usize);
+
+    Ok(SessionHistoryPacket {
+        header,
+        raw: None,
+        car_index,
+        best_lap_time_lap_num,
+        best_sector_1_lap_num,
+        best_sector_2_lap_num,
+        best_sector_3_lap_num,
+        lap_history_data,
+        tyre_stint_history_data,
+    })
+}
@@ -0,0 +1,52 @@
+use std::io::Cursor;
+
+use bytes::{Buf, Bytes};
+
+use crate::{
+    f1_23::car_status::{parse_tyre_compound_actual, parse_tyre_compound_visual},
+    packet::tyre_sets::{TyreSetData, TyreSetsPacket},
+};
+
+use super::{header::parse_header, session::parse_session_type};
+
+pub fn parse_tyre_sets_packet(cursor: &mut Cursor<Bytes>) -> crate::Result<TyreSetsPacket> {
+    crate::check_packet_length(cursor, "tyre sets", 231)?;
+
+    let header = parse_header(cursor)?;
+
+    let car_index = cursor.get_u8();
+    let tyre_set_data = (0..20).map(|_| parse_tyre_set_data(cursor)).collect();
+    let fitted_index = cursor.get_u8();
+
+    Ok(TyreSetsPacket {
+        header,
+        raw: None,
+        car_index,
+        tyre_set_data,
+        fitted_index,
+    })
+}
+
+fn parse_tyre_set_data(cursor: &mut Cursor<Bytes>) -> TyreSetData {
+    let actual_tyre_compound = parse_tyre_compound_actual(cursor.get_u8());
+    let visual_tyre_compound = parse_tyre_compound_visual(cursor.get_u8());
+    let wear = cursor.get_u8();
+    let available = cursor.get_u8() != 0;
+    let recommended_session = parse_session_type(cursor.get_u8());
+    let life_span = cursor.get_u8();
+    let usable_life = cursor.get_u8();
+    let lap_delta_time_in_ms = cursor.get_i16_le();
+    let fitted = cursor.get_u8() != 0;
+
+    TyreSetData {
+        actual_tyre_compound,
+        visual_tyre_compound,
+        wear,
+        available,
+        recommended_session,
+        life_span,
+        usable_life,
+        lap_delta_time_in_ms,
+        fitted,
+    }
+}
@@ -2,10 +2,18 @@ use std::io::{self, Cursor};
 
 use bytes::{Buf, Bytes};
 
-use self::packet::Packet;
+use self::packet::{header::Format, Packet};
 
+pub mod metadata;
 pub mod packet;
+pub mod session_state;
+#[cfg(feature = "listener")]
+pub mod listener;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+mod f1_22;
 mod f1_23;
+mod f1_24;
 
 #[derive(thiserror::Error, Debug)]
 pub enum TelemetryError {
@@ -18,25 +26,116 @@ pub enum TelemetryError {
 type Result<T> = std::result::Result<T, TelemetryError>;
 
 pub fn decode_packet(bytes: Bytes) -> Result<Packet> {
-    let mut cursor = Cursor::new(bytes);
+    let mut cursor = Cursor::new(bytes.clone());
 
-    let format = cursor.get_u16_le();
+    let format = parse_format(&mut cursor)?;
 
-    match format {
+    let mut packet = match format {
+        2022 => f1_22::decode_twentytwo(&mut cursor),
         2023 => f1_23::decode_twentythree(&mut cursor),
+        2024 => f1_24::decode_twentyfour(&mut cursor),
         _ => Err(TelemetryError::InvalidPacket("Unsupported format".to_owned())),
+    }?;
+    packet.set_raw(bytes);
+
+    Ok(packet)
+}
+
+/// Encodes an F1 23 packet back to the wire format.
+///
+/// What the model doesn't keep is taken from the datagram the packet was decoded from: raw ids
+/// that decode to the same value or to a fallback, padding, unused flag bits, what follows the end
+/// of names, the data of inactive cars and the entries past a count. A decoded packet encodes back
+/// to the same bytes, and only the fields changed since are written from the model. Packets that
+/// weren't decoded from a datagram, or whose changes can't be told apart from the bytes around
+/// them, are written from the model alone, with zeros for what it doesn't keep.
+///
+/// Packets of other games are rejected rather than written in the wrong layout.
+pub fn encode_packet(packet: &Packet) -> Result<Bytes> {
+    let format = packet.header().format;
+    if !matches!(format, Format::TwentyThree) {
+        return Err(TelemetryError::InvalidPacket(format!(
+            "can only encode F1 23 packets, got {format:?}"
+        )));
+    }
+
+    let encoded = f1_23::encode_twentythree(packet);
+    let Some(raw) = packet.raw() else {
+        return Ok(encoded);
+    };
+
+    // The packet as it was decoded, the bytes the encoding differs from it in were changed since
+    let decoded = f1_23::encode_twentythree(&decode_packet(raw.clone())?);
+    if raw.len() != encoded.len() || decoded.len() != encoded.len() {
+        return Ok(encoded);
+    }
+
+    let merged = keep_raw_bytes(raw, &decoded, &encoded);
+    // The raw bytes that were kept must not change what the packet decodes to
+    match decode_packet(merged.clone()) {
+        Ok(packet) if f1_23::encode_twentythree(&packet) == encoded => Ok(merged),
+        _ => Ok(encoded),
+    }
+}
+
+/// Takes the bytes that weren't changed from the raw datagram. The bytes the model doesn't keep
+/// next to a changed byte are usually part of the same field (like what follows the end of a
+/// name), so they're written from the model as well.
+fn keep_raw_bytes(raw: &[u8], decoded: &[u8], encoded: &[u8]) -> Bytes {
+    let lossy = |i: usize| raw[i] != decoded[i];
+    let mut changed: Vec<bool> = (0..raw.len()).map(|i| encoded[i] != decoded[i]).collect();
+    for i in 1..raw.len() {
+        changed[i] |= changed[i - 1] && lossy(i);
+    }
+    for i in (0..raw.len().saturating_sub(1)).rev() {
+        changed[i] |= changed[i + 1] && lossy(i);
     }
+
+    (0..raw.len())
+        .map(|i| if changed[i] { encoded[i] } else { raw[i] })
+        .collect()
 }
 
 pub fn decode_header(bytes: Bytes) -> Result<packet::header::Header> {
     let mut cursor = Cursor::new(bytes);
 
-    let format = cursor.get_u16_le();
-    
-    cursor.set_position(0);
+    let format = parse_format(&mut cursor)?;
 
     match format {
+        2022 => f1_22::decode_twentytwo_header(&mut cursor),
         2023 => f1_23::decode_twentythree_header(&mut cursor),
+        2024 => f1_24::decode_twentyfour_header(&mut cursor),
         _ => Err(TelemetryError::InvalidPacket("Unsupported format".to_owned())),
     }
-}
\ No newline at end of file
+}
+
+/// Peeks at the packet format, leaving the cursor at the start of the packet
+fn parse_format(cursor: &mut Cursor<Bytes>) -> Result<u16> {
+    if cursor.remaining() < 2 {
+        return Err(TelemetryError::InvalidPacket(format!(
+            "packet too small to contain a format, got {} bytes",
+            cursor.remaining()
+        )));
+    }
+
+    let format = cursor.get_u16_le();
+    cursor.set_position(0);
+
+    Ok(format)
+}
+
+/// Checks that the packet has exactly the size the game sends for its packet id
+pub(crate) fn check_packet_length(
+    cursor: &Cursor<Bytes>,
+    packet: &str,
+    expected: usize,
+) -> Result<()> {
+    let actual = cursor.remaining();
+    if actual != expected {
+        return Err(TelemetryError::InvalidPacket(format!(
+            "invalid {packet} packet length, expected {expected} bytes but got {actual}"
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::{io, net::SocketAddr};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use tokio::net::{ToSocketAddrs, UdpSocket};
+
+use crate::{decode_packet, packet::Packet, TelemetryError};
+
+/// Large enough for the biggest packet any supported game sends
+const MAX_PACKET_SIZE: usize = 2048;
+
+/// A decoded packet together with the datagram it was decoded from
+#[derive(Clone, Debug)]
+pub struct ReceivedPacket {
+    pub received_at: DateTime<Utc>,
+    pub bytes: Bytes,
+    pub packet: Packet,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RecvError {
+    #[error("io error")]
+    Io(#[from] io::Error),
+    /// The datagram is kept, so it can still be logged or recorded
+    #[error("could not decode datagram: {error}")]
+    Invalid {
+        received_at: DateTime<Utc>,
+        bytes: Bytes,
+        #[source]
+        error: TelemetryError,
+    },
+}
+
+/// Listens for the UDP telemetry sent by the game
+pub struct Listener {
+    socket: UdpSocket,
+}
+
+impl Listener {
+    pub async fn bind(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address).await?;
+        Ok(Self { socket })
+    }
+
+    /// Listens on a socket that was already bound, for sockets that need options `bind` doesn't
+    /// set. Has to be called from within a tokio runtime.
+    pub fn from_std(socket: std::net::UdpSocket) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+        let socket = UdpSocket::from_std(socket)?;
+        Ok(Self { socket })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Receives a single datagram and decodes it
+    pub async fn recv(&self) -> Result<ReceivedPacket, RecvError> {
+        let mut buf = vec![0; MAX_PACKET_SIZE];
+        let (len, _) = self.socket.recv_from(&mut buf).await?;
+        let received_at = Utc::now();
+        buf.truncate(len);
+
+        let bytes = Bytes::from(buf);
+        match decode_packet(bytes.clone()) {
+            Ok(packet) => Ok(ReceivedPacket {
+                received_at,
+                bytes,
+                packet,
+            }),
+            Err(error) => Err(RecvError::Invalid {
+                received_at,
+                bytes,
+                error,
+            }),
+        }
+    }
+
+    /// Turns the listener into an endless stream of packets.
+    ///
+    /// Datagrams that fail to decode are yielded as errors, the stream keeps going after them.
+    pub fn into_stream(self) -> impl Stream<Item = Result<ReceivedPacket, RecvError>> {
+        futures::stream::unfold(self, |listener| async move {
+            let packet = listener.recv().await;
+            Some((packet, listener))
+        })
+    }
+}
@@ -0,0 +1,4 @@
+//! Static data about game ids that isn't sent in the packets themselves
+
+pub mod team;
+pub mod track;
@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+
+use crate::packet::participants::Team;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Series {
+    F1,
+    F2,
+    Other,
+}
+
+/// Static data about a team in the game
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct TeamInfo {
+    pub name: &'static str,
+    /// Name the team is usually called by, used to match it to league teams
+    pub short_name: &'static str,
+    pub series: Series,
+    pub nationality: Option<celes::Country>,
+    /// Hex colour code (e.g. `#E8002D`), only known for F1 teams
+    pub colour: Option<&'static str>,
+}
+
+impl TeamInfo {
+    const fn new(
+        name: &'static str,
+        short_name: &'static str,
+        series: Series,
+        nationality: Option<celes::Country>,
+        colour: Option<&'static str>,
+    ) -> Self {
+        Self {
+            name,
+            short_name,
+            series,
+            nationality,
+            colour,
+        }
+    }
+}
+
+/// A team as the league knows it, e.g. a row of the backend's `teams` table
+pub trait LeagueTeam {
+    fn name(&self) -> &str;
+    /// ISO 3166-1 alpha-2 code
+    fn nationality(&self) -> &str;
+}
+
+impl Team {
+    pub fn info(&self) -> TeamInfo {
+        use celes::Country;
+        use Series::{Other, F1, F2};
+
+        let uk = Country::the_united_kingdom_of_great_britain_and_northern_ireland;
+
+        match self {
+            Team::Unknown => TeamInfo::new("Unknown", "Unknown", Other, None, None),
+            Team::Mercedes => TeamInfo::new(
+                "Mercedes-AMG Petronas F1 Team",
+                "Mercedes",
+                F1,
+                Some(Country::germany()),
+                Some("#27F4D2"),
+            ),
+            Team::Ferrari => TeamInfo::new(
+                "Scuderia Ferrari",
+                "Ferrari",
+                F1,
+                Some(Country::italy()),
+                Some("#E8002D"),
+            ),
+            Team::RedBullRacing => TeamInfo::new(
+                "Oracle Red Bull Racing",
+                "Red Bull",
+                F1,
+                Some(Country::austria()),
+                Some("#3671C6"),
+            ),
+            Team::Williams => TeamInfo::new(
+                "Williams Racing",
+                "Williams",
+                F1,
+                Some(uk()),
+                Some("#64C4FF"),
+            ),
+            Team::AstonMartin => TeamInfo::new(
+                "Aston Martin Aramco F1 Team",
+                "Aston Martin",
+                F1,
+                Some(uk()),
+                Some("#229971"),
+            ),
+            Team::Alpine => TeamInfo::new(
+                "BWT Alpine F1 Team",
+                "Alpine",
+                F1,
+                Some(Country::france()),
+                Some("#0093CC"),
+            ),
+            Team::AlphaTauri => TeamInfo::new(
+                "Scuderia AlphaTauri",
+                "AlphaTauri",
+                F1,
+                Some(Country::italy()),
+                Some("#5E8FAA"),
+            ),
+            Team::Haas => TeamInfo::new(
+                "MoneyGram Haas F1 Team",
+                "Haas",
+                F1,
+                Some(Country::the_united_states_of_america()),
+                Some("#B6BABD"),
+            ),
+            Team::McLaren => TeamInfo::new(
+                "McLaren F1 Team",
+                "McLaren",
+                F1,
+                Some(uk()),
+                Some("#FF8000"),
+            ),
+            Team::AlfaRomeo => TeamInfo::new(
+                "Alfa Romeo F1 Team Stake",
+                "Alfa Romeo",
+                F1,
+                Some(Country::switzerland()),
+                Some("#C92D4B"),
+            ),
+            Team::RB => TeamInfo::new(
+                "Visa Cash App RB F1 Team",
+                "RB",
+                F1,
+                Some(Country::italy()),
+                Some("#6692FF"),
+            ),
+            Team::Sauber => TeamInfo::new(
+                "Stake F1 Team Kick Sauber",
+                "Sauber",
+                F1,
+                Some(Country::switzerland()),
+                Some("#52E252"),
+            ),
+            Team::RacingPoint => TeamInfo::new(
+                "BWT Racing Point F1 Team",
+                "Racing Point",
+                F1,
+                Some(uk()),
+                Some("#F596C8"),
+            ),
+            Team::Renault => TeamInfo::new(
+                "Renault DP World F1 Team",
+                "Renault",
+                F1,
+                Some(Country::france()),
+                Some("#FFF500"),
+            ),
+            Team::Konnersport => TeamInfo::new("Konnersport", "Konnersport", Other, None, None),
+            Team::ArtGP => {
+                TeamInfo::new("ART Grand Prix", "ART", F2, Some(Country::france()), None)
+            }
+            Team::Campos => {
+                TeamInfo::new("Campos Racing", "Campos", F2, Some(Country::spain()), None)
+            }
+            Team::Carlin => TeamInfo::new("Carlin", "Carlin", F2, Some(uk()), None),
+            Team::SauberJuniorCharouz => TeamInfo::new(
+                "Sauber Junior Team by Charouz",
+                "Sauber Junior",
+                F2,
+                Some(Country::czechia()),
+                None,
+            ),
+            Team::Dams => TeamInfo::new("DAMS", "DAMS", F2, Some(Country::france()), None),
+            Team::UniVirtuosi => {
+                TeamInfo::new("UNI-Virtuosi Racing", "UNI-Virtuosi", F2, Some(uk()), None)
+            }
+            Team::Virtuosi => TeamInfo::new("Virtuosi Racing", "Virtuosi", F2, Some(uk()), None),
+            Team::MPMotorsport => TeamInfo::new(
+                "MP Motorsport",
+                "MP Motorsport",
+                F2,
+                Some(Country::the_netherlands()),
+                None,
+            ),
+            Team::Prema => TeamInfo::new("Prema Racing", "Prema", F2, Some(Country::italy()), None),
+            Team::Trident => TeamInfo::new("Trident", "Trident", F2, Some(Country::italy()), None),
+            Team::Arden => TeamInfo::new("BWT Arden", "Arden", F2, Some(uk()), None),
+            Team::Charouz => TeamInfo::new(
+                "Charouz Racing System",
+                "Charouz",
+                F2,
+                Some(Country::czechia()),
+                None,
+            ),
+            Team::BWT => {
+                TeamInfo::new("BWT HWA Racelab", "HWA", F2, Some(Country::germany()), None)
+            }
+            Team::Hitech => TeamInfo::new("Hitech Grand Prix", "Hitech", F2, Some(uk()), None),
+            Team::PHM => TeamInfo::new(
+                "PHM Racing by Charouz",
+                "PHM",
+                F2,
+                Some(Country::germany()),
+                None,
+            ),
+            Team::VanAmersfoortRacing => TeamInfo::new(
+                "Van Amersfoort Racing",
+                "Van Amersfoort",
+                F2,
+                Some(Country::the_netherlands()),
+                None,
+            ),
+            Team::Supercar => TeamInfo::new("Supercar", "Supercar", Other, None, None),
+            Team::SafetyCar => TeamInfo::new("Safety Car", "Safety Car", Other, None, None),
+            Team::F1Generic => TeamInfo::new("F1 Generic", "F1 Generic", F1, None, None),
+            Team::CustomTeam => TeamInfo::new("My Team", "My Team", F1, None, None),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.info().name
+    }
+
+    /// Finds the league team that races as this team.
+    ///
+    /// League teams match when every word of the team's short name is in their name, so "Oracle
+    /// Red Bull Racing" matches Red Bull but not RB. When several teams match, the one with the
+    /// same nationality wins.
+    pub fn find_league_team<'a, T: LeagueTeam>(&self, teams: &'a [T]) -> Option<&'a T> {
+        let info = self.info();
+        let short_name = words(info.short_name);
+
+        let mut candidates = teams.iter().filter(|team| {
+            let name = words(team.name());
+            short_name.iter().all(|word| name.contains(word))
+        });
+
+        let first = candidates.next()?;
+        let nationality_matches = |team: &&T| {
+            info.nationality
+                .is_some_and(|country| country.alpha2.eq_ignore_ascii_case(team.nationality()))
+        };
+
+        if nationality_matches(&first) {
+            return Some(first);
+        }
+
+        Some(candidates.find(nationality_matches).unwrap_or(first))
+    }
+}
+
+fn words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
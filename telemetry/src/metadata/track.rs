@@ -0,0 +1,277 @@
+use serde::Serialize;
+
+use crate::packet::session::Track;
+
+/// Static data about a track layout in the game.
+///
+/// Sector boundaries aren't included, there's no published source for where they are on the
+/// game's layouts. The lap distance at which a sector starts can be read from the lap data packets.
+#[derive(Copy, Clone, Debug, Serialize)]
+pub struct TrackInfo {
+    /// Short identifier, the backend uses it as the track's id
+    pub id: &'static str,
+    pub name: &'static str,
+    pub country: celes::Country,
+    /// Nominal lap length in metres, the game sends the exact one in the session packet
+    pub length: u16,
+    /// Pit lane speed limit in km/h, the game sends the one in use in the session packet
+    pub pit_speed_limit: u8,
+    /// Whether this is a shortened layout of another track
+    pub short_layout: bool,
+}
+
+impl TrackInfo {
+    const fn new(
+        id: &'static str,
+        name: &'static str,
+        country: celes::Country,
+        length: u16,
+        pit_speed_limit: u8,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            country,
+            length,
+            pit_speed_limit,
+            short_layout: false,
+        }
+    }
+
+    const fn short(self) -> Self {
+        Self {
+            short_layout: true,
+            ..self
+        }
+    }
+}
+
+impl Track {
+    /// Every track the game can send
+    pub const ALL: [Track; 33] = [
+        Track::Melbourne,
+        Track::PaulRicard,
+        Track::Shanghai,
+        Track::Sakhir,
+        Track::Catalunya,
+        Track::Monaco,
+        Track::Montreal,
+        Track::Silverstone,
+        Track::Hockenheim,
+        Track::Hungaroring,
+        Track::Spa,
+        Track::Monza,
+        Track::Singapore,
+        Track::Suzuka,
+        Track::AbuDhabi,
+        Track::Texas,
+        Track::Brazil,
+        Track::Austria,
+        Track::Sochi,
+        Track::Mexico,
+        Track::Baku,
+        Track::SakhirShort,
+        Track::SilverstoneShort,
+        Track::TexasShort,
+        Track::SuzukaShort,
+        Track::Hanoi,
+        Track::Zandvoort,
+        Track::Imola,
+        Track::Portimao,
+        Track::Jeddah,
+        Track::Miami,
+        Track::Vegas,
+        Track::Losail,
+    ];
+
+    /// Static data about the track, `None` for tracks the game sent an unknown id for
+    pub fn info(&self) -> Option<TrackInfo> {
+        use celes::Country;
+
+        let info = match self {
+            Track::Unknown => return None,
+            Track::Melbourne => {
+                TrackInfo::new("melbourne", "Albert Park", Country::australia(), 5278, 80)
+            }
+            Track::PaulRicard => TrackInfo::new(
+                "paul_ricard",
+                "Circuit Paul Ricard",
+                Country::france(),
+                5842,
+                80,
+            ),
+            Track::Shanghai => TrackInfo::new(
+                "shanghai",
+                "Shanghai International Circuit",
+                Country::china(),
+                5451,
+                80,
+            ),
+            Track::Sakhir => TrackInfo::new(
+                "sakhir",
+                "Bahrain International Circuit",
+                Country::bahrain(),
+                5412,
+                80,
+            ),
+            Track::Catalunya => TrackInfo::new(
+                "catalunya",
+                "Circuit de Barcelona-Catalunya",
+                Country::spain(),
+                4657,
+                80,
+            ),
+            Track::Monaco => TrackInfo::new("monaco", "Monaco", Country::monaco(), 3337, 60),
+            Track::Montreal => TrackInfo::new(
+                "montreal",
+                "Circuit Gilles Villeneuve",
+                Country::canada(),
+                4361,
+                80,
+            ),
+            Track::Silverstone => TrackInfo::new(
+                "silverstone",
+                "Silverstone",
+                Country::the_united_kingdom_of_great_britain_and_northern_ireland(),
+                5891,
+                80,
+            ),
+            Track::Hockenheim => {
+                TrackInfo::new("hockenheim", "Hockenheimring", Country::germany(), 4574, 80)
+            }
+            Track::Hungaroring => {
+                TrackInfo::new("hungaroring", "Hungaroring", Country::hungary(), 4381, 80)
+            }
+            Track::Spa => TrackInfo::new("spa", "Spa-Francorchamps", Country::belgium(), 7004, 80),
+            Track::Monza => TrackInfo::new("monza", "Monza", Country::italy(), 5793, 80),
+            Track::Singapore => TrackInfo::new(
+                "singapore",
+                "Marina Bay Street Circuit",
+                Country::singapore(),
+                4940,
+                60,
+            ),
+            Track::Suzuka => TrackInfo::new("suzuka", "Suzuka", Country::japan(), 5807, 80),
+            Track::AbuDhabi => TrackInfo::new(
+                "abu_dhabi",
+                "Yas Marina Circuit",
+                Country::the_united_arab_emirates(),
+                5281,
+                80,
+            ),
+            Track::Texas => TrackInfo::new(
+                "texas",
+                "Circuit of the Americas",
+                Country::the_united_states_of_america(),
+                5513,
+                80,
+            ),
+            Track::Brazil => TrackInfo::new("brazil", "Interlagos", Country::brazil(), 4309, 80),
+            Track::Austria => {
+                TrackInfo::new("austria", "Red Bull Ring", Country::austria(), 4318, 80)
+            }
+            Track::Sochi => TrackInfo::new(
+                "sochi",
+                "Sochi Autodrom",
+                Country::the_russian_federation(),
+                5848,
+                80,
+            ),
+            Track::Mexico => TrackInfo::new(
+                "mexico",
+                "Autódromo Hermanos Rodríguez",
+                Country::mexico(),
+                4304,
+                80,
+            ),
+            Track::Baku => {
+                TrackInfo::new("baku", "Baku City Circuit", Country::azerbaijan(), 6003, 80)
+            }
+            Track::SakhirShort => TrackInfo::new(
+                "sakhir_short",
+                "Bahrain International Circuit (Short)",
+                Country::bahrain(),
+                3543,
+                80,
+            )
+            .short(),
+            Track::SilverstoneShort => TrackInfo::new(
+                "silverstone_short",
+                "Silverstone (Short)",
+                Country::the_united_kingdom_of_great_britain_and_northern_ireland(),
+                2639,
+                80,
+            )
+            .short(),
+            Track::TexasShort => TrackInfo::new(
+                "texas_short",
+                "Circuit of the Americas (Short)",
+                Country::the_united_states_of_america(),
+                3700,
+                80,
+            )
+            .short(),
+            Track::SuzukaShort => {
+                TrackInfo::new("suzuka_short", "Suzuka (Short)", Country::japan(), 2243, 80).short()
+            }
+            Track::Hanoi => TrackInfo::new("hanoi", "Hanoi Circuit", Country::vietnam(), 5607, 80),
+            Track::Zandvoort => TrackInfo::new(
+                "zandvoort",
+                "Circuit Zandvoort",
+                Country::the_netherlands(),
+                4259,
+                60,
+            ),
+            Track::Imola => TrackInfo::new("imola", "Imola", Country::italy(), 4909, 80),
+            Track::Portimao => TrackInfo::new(
+                "portimao",
+                "Algarve International Circuit",
+                Country::portugal(),
+                4653,
+                80,
+            ),
+            Track::Jeddah => TrackInfo::new(
+                "jeddah",
+                "Jeddah Corniche Circuit",
+                Country::saudi_arabia(),
+                6174,
+                80,
+            ),
+            Track::Miami => TrackInfo::new(
+                "miami",
+                "Miami International Autodrome",
+                Country::the_united_states_of_america(),
+                5412,
+                80,
+            ),
+            Track::Vegas => TrackInfo::new(
+                "vegas",
+                "Las Vegas Strip Circuit",
+                Country::the_united_states_of_america(),
+                6201,
+                80,
+            ),
+            Track::Losail => TrackInfo::new(
+                "losail",
+                "Lusail International Circuit",
+                Country::qatar(),
+                5419,
+                80,
+            ),
+        };
+
+        Some(info)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.info().map(|info| info.name).unwrap_or("Unknown")
+    }
+
+    pub fn country(&self) -> Option<celes::Country> {
+        self.info().map(|info| info.country)
+    }
+
+    pub fn is_short_layout(&self) -> bool {
+        self.info().is_some_and(|info| info.short_layout)
+    }
+}
@@ -0,0 +1,43 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::header::Header;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct CarDamageData {
+    /// Tyre wear (percentage), in the order RL, RR, FL, FR
+    pub tyres_wear: [f32; 4],
+    /// Tyre damage (percentage), in the order RL, RR, FL, FR
+    pub tyres_damage: [u8; 4],
+    /// Brake damage (percentage), in the order RL, RR, FL, FR
+    pub brakes_damage: [u8; 4],
+    pub front_left_wing_damage: u8,
+    pub front_right_wing_damage: u8,
+    pub rear_wing_damage: u8,
+    pub floor_damage: u8,
+    pub diffuser_damage: u8,
+    pub sidepod_damage: u8,
+    pub drs_fault: bool,
+    pub ers_fault: bool,
+    pub gearbox_damage: u8,
+    pub engine_damage: u8,
+    pub engine_mguh_wear: u8,
+    pub engine_es_wear: u8,
+    pub engine_ce_wear: u8,
+    pub engine_ice_wear: u8,
+    pub engine_mguk_wear: u8,
+    pub engine_tc_wear: u8,
+    pub engine_blown: bool,
+    pub engine_seized: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CarDamagePacket {
+    pub header: Header,
+
+    pub car_damage_data: Vec<CarDamageData>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
+}
@@ -0,0 +1,47 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::header::Header;
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct CarSetupData {
+    pub front_wing: u8,
+    pub rear_wing: u8,
+    /// Differential adjustment on throttle (percentage)
+    pub on_throttle: u8,
+    /// Differential adjustment off throttle (percentage)
+    pub off_throttle: u8,
+    pub front_camber: f32,
+    pub rear_camber: f32,
+    pub front_toe: f32,
+    pub rear_toe: f32,
+    pub front_suspension: u8,
+    pub rear_suspension: u8,
+    pub front_anti_roll_bar: u8,
+    pub rear_anti_roll_bar: u8,
+    pub front_suspension_height: u8,
+    pub rear_suspension_height: u8,
+    /// Brake pressure (percentage)
+    pub brake_pressure: u8,
+    /// Front brake bias (percentage)
+    pub brake_bias: u8,
+    /// Engine braking (percentage), only sent by F1 24 and later
+    pub engine_braking: Option<u8>,
+    /// Tyre pressures in PSI, in the order RL, RR, FL, FR
+    pub tyres_pressure: [f32; 4],
+    pub ballast: u8,
+    pub fuel_load: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CarSetupsPacket {
+    pub header: Header,
+
+    pub car_setups: Vec<CarSetupData>,
+    /// Front wing value after the next pit stop (player only), only sent by F1 24 and later
+    pub next_front_wing_value: Option<f32>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
+}
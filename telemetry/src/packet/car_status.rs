@@ -1,3 +1,4 @@
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use super::{header::Header, session::MarshalFlag};
@@ -65,8 +66,10 @@ pub struct CarStatusData {
     pub visual_tyre_compound: TyreCompound,
     pub tyres_age_laps: u8,
     pub vehicle_fia_flags: MarshalFlag,
-    pub engine_power_ice: f32,
-    pub engine_power_mguk: f32,
+    /// Not sent by F1 22
+    pub engine_power_ice: Option<f32>,
+    /// Not sent by F1 22
+    pub engine_power_mguk: Option<f32>,
     pub ers_store_energy: f32,
     pub ers_deploy_mode: ERSDeployMode,
     pub ers_harvested_this_lap_mguk: f32,
@@ -80,4 +83,8 @@ pub struct CarStatusPacket {
     pub header: Header,
 
     pub car_status_data: Vec<CarStatusData>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
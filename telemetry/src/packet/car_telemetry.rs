@@ -1,3 +1,4 @@
+use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 
 use super::header::Header;
@@ -58,4 +59,8 @@ pub struct CarTelemetryPacket {
     pub mfd_panel_index: MFDPanelIndex,
     pub mfd_panel_index_secondary_player: MFDPanelIndex,
     pub suggested_gear: i8,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
@@ -1,8 +1,10 @@
 use std::time::Duration;
 
+use bitflags::bitflags;
+use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 
-use super::header::Header;
+use super::{header::Header, session::SafetyCarStatus};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct FastestLap {
@@ -128,9 +130,110 @@ pub struct Flashback {
     pub flashback_session_time: f32,
 }
 
+bitflags! {
+    /// Buttons that are held down, controller buttons are named after both the PlayStation and
+    /// the Xbox button in the same spot
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct ButtonFlags: u32 {
+        const CROSS_OR_A = 0x0000_0001;
+        const TRIANGLE_OR_Y = 0x0000_0002;
+        const CIRCLE_OR_B = 0x0000_0004;
+        const SQUARE_OR_X = 0x0000_0008;
+        const DPAD_LEFT = 0x0000_0010;
+        const DPAD_RIGHT = 0x0000_0020;
+        const DPAD_UP = 0x0000_0040;
+        const DPAD_DOWN = 0x0000_0080;
+        const OPTIONS_OR_MENU = 0x0000_0100;
+        const L1_OR_LB = 0x0000_0200;
+        const R1_OR_RB = 0x0000_0400;
+        const L2_OR_LT = 0x0000_0800;
+        const R2_OR_RT = 0x0000_1000;
+        const LEFT_STICK_CLICK = 0x0000_2000;
+        const RIGHT_STICK_CLICK = 0x0000_4000;
+        const RIGHT_STICK_LEFT = 0x0000_8000;
+        const RIGHT_STICK_RIGHT = 0x0001_0000;
+        const RIGHT_STICK_UP = 0x0002_0000;
+        const RIGHT_STICK_DOWN = 0x0004_0000;
+        const SPECIAL = 0x0008_0000;
+        const UDP_ACTION_1 = 0x0010_0000;
+        const UDP_ACTION_2 = 0x0020_0000;
+        const UDP_ACTION_3 = 0x0040_0000;
+        const UDP_ACTION_4 = 0x0080_0000;
+        const UDP_ACTION_5 = 0x0100_0000;
+        const UDP_ACTION_6 = 0x0200_0000;
+        const UDP_ACTION_7 = 0x0400_0000;
+        const UDP_ACTION_8 = 0x0800_0000;
+        const UDP_ACTION_9 = 0x1000_0000;
+        const UDP_ACTION_10 = 0x2000_0000;
+        const UDP_ACTION_11 = 0x4000_0000;
+        const UDP_ACTION_12 = 0x8000_0000;
+    }
+}
+
+impl ButtonFlags {
+    const FIRST_UDP_ACTION_BIT: u32 = 20;
+
+    /// The flag of UDP action button 1 to 12, the ones that can be bound in the game's settings
+    pub fn udp_action(action: u8) -> Option<Self> {
+        (1..=12)
+            .contains(&action)
+            .then(|| Self::from_bits_retain(1 << (Self::FIRST_UDP_ACTION_BIT + action as u32 - 1)))
+    }
+
+    /// Numbers (1 to 12) of the UDP action buttons in this set
+    pub fn udp_actions(self) -> impl Iterator<Item = u8> {
+        (1..=12).filter(move |&action| Self::udp_action(action).is_some_and(|f| self.contains(f)))
+    }
+
+    /// Buttons that are held now but weren't in the previous button event
+    pub fn pressed_since(self, previous: Self) -> Self {
+        self.difference(previous)
+    }
+
+    /// Buttons that were held in the previous button event but aren't anymore
+    pub fn released_since(self, previous: Self) -> Self {
+        previous.difference(self)
+    }
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Buttons {
-    pub button_status: u32,
+    pub button_status: ButtonFlags,
+}
+
+/// Tracks the buttons that are held down across button events to find press and release edges.
+///
+/// The game only sends a button event when the state changes, so every event is compared to the
+/// one before it.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ButtonTracker {
+    held: ButtonFlags,
+}
+
+/// Buttons that changed state in a single button event
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ButtonEdges {
+    pub pressed: ButtonFlags,
+    pub released: ButtonFlags,
+}
+
+impl ButtonTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn held(&self) -> ButtonFlags {
+        self.held
+    }
+
+    pub fn update(&mut self, buttons: Buttons) -> ButtonEdges {
+        let edges = ButtonEdges {
+            pressed: buttons.button_status.pressed_since(self.held),
+            released: buttons.button_status.released_since(self.held),
+        };
+        self.held = buttons.button_status;
+        edges
+    }
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -140,6 +243,35 @@ pub struct Overtake {
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Collision {
+    pub vehicle_1_idx: u8,
+    pub vehicle_2_idx: u8,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SafetyCarEventType {
+    Deployed,
+    Returning,
+    Returned,
+    ResumeRace,
+    Unknown,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct SafetyCar {
+    pub safety_car_type: SafetyCarStatus,
+    pub event_type: SafetyCarEventType,
+}
+
+/// An event this crate doesn't know about, e.g. one added by a game patch
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnknownEvent {
+    pub code: [u8; 4],
+    /// Everything after the code, including any padding the game sent
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Event {
     SessionStarted,
     SessionEnded,
@@ -160,11 +292,19 @@ pub enum Event {
     Button(Buttons),
     RedFlag,
     Overtake(Overtake),
-    Unknown,
+    /// Only sent by F1 24 and later
+    SafetyCar(SafetyCar),
+    /// Only sent by F1 24 and later
+    Collision(Collision),
+    Unknown(UnknownEvent),
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EventPacket {
     pub header: Header,
     pub event: Event,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 
 use super::{header::Header, lap_data::ResultStatus, car_status::TyreCompound};
@@ -31,4 +32,8 @@ pub struct FinalClassificationPacket {
     pub header: Header,
 
     pub classification_data: Vec<Option<FinalClassificationData>>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
@@ -3,14 +3,18 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Format {
+    #[serde(rename = "F1 2022")]
+    TwentyTwo,
     #[serde(rename = "F1 2023")]
     TwentyThree,
+    #[serde(rename = "F1 2024")]
+    TwentyFour,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct GameVersion(pub u8, pub u8);
 
-#[derive(Copy, Clone, Debug, Display, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Display, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum PacketId {
     Motion,
     Session,
@@ -39,8 +43,9 @@ pub struct Header {
     pub session_time: f32,
     /// Identifier for what frame the data was retrieved on
     pub frame_identifier: u32,
-    /// Overal identifier for the frame the data was retrieved on, doesn't go back after flashbacks
-    pub overall_frame_identifier: u32,
+    /// Overal identifier for the frame the data was retrieved on, doesn't go back after flashbacks.
+    /// Not sent by F1 22
+    pub overall_frame_identifier: Option<u32>,
     pub player_car_index: u8,
     pub secondary_player_car_index: Option<u8>,
 }
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 
 use super::header::Header;
@@ -54,8 +55,10 @@ pub struct LapData {
     pub current_lap_time: Duration,
     pub sector_1_time: Duration,
     pub sector_2_time: Duration,
-    pub delta_to_car_in_front: Duration,
-    pub delta_to_race_leader: Duration,
+    /// Not sent by F1 22
+    pub delta_to_car_in_front: Option<Duration>,
+    /// Not sent by F1 22
+    pub delta_to_race_leader: Option<Duration>,
     pub lap_distance: f32,
     pub total_distance: f32,
     pub safety_car_delta: f32,
@@ -67,7 +70,8 @@ pub struct LapData {
     pub current_lap_invalid: bool,
     pub penalties: u8,
     pub warnings: u8,
-    pub corner_cutting_warnings: u8,
+    /// Not sent by F1 22
+    pub corner_cutting_warnings: Option<u8>,
     pub num_unserved_drive_through_pens: u8,
     pub num_unserved_stop_go_pens: u8,
     pub grid_position: u8,
@@ -77,6 +81,10 @@ pub struct LapData {
     pub pit_lane_time_in_lane: Duration,
     pub pit_stop_timer: Duration,
     pub pit_stop_should_serve_pen: bool,
+    /// Fastest speed through the speed trap for this car in kmph, only sent by F1 24 and later
+    pub speed_trap_fastest_speed: Option<f32>,
+    /// Lap on which the fastest speed trap was set, only sent by F1 24 and later
+    pub speed_trap_fastest_lap: Option<u8>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -85,4 +93,8 @@ pub struct LapDataPacket {
     pub lap_data: Vec<Option<LapData>>,
     pub time_trial_pb_car_idx: Option<u8>,
     pub time_trial_rival_car_idx: Option<u8>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
@@ -1,6 +1,7 @@
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
-use super::{header::Header, participants::{Platform, Team}};
+use super::{header::Header, participants::{Platform, Team, Telemetry}};
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum Status {
@@ -17,6 +18,12 @@ pub struct LobbyInfoData {
     pub platform: Option<Platform>,
     pub name: String,
     pub car_number: u8,
+    /// Only sent by F1 24 and later
+    pub your_telemetry: Option<Telemetry>,
+    /// Only sent by F1 24 and later
+    pub show_online_names: Option<bool>,
+    /// F1 World tech level, only sent by F1 24 and later
+    pub tech_level: Option<u16>,
     pub ready_status: Status,
 }
 
@@ -25,4 +32,8 @@ pub struct LobbyInfoPacket {
     pub header: Header,
 
     pub lobby_players: Vec<LobbyInfoData>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
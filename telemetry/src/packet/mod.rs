@@ -1,5 +1,8 @@
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
+pub mod car_damage;
+pub mod car_setups;
 pub mod car_status;
 pub mod car_telemetry;
 pub mod event;
@@ -7,10 +10,12 @@ pub mod final_classification;
 pub mod header;
 pub mod lap_data;
 pub mod motion;
+pub mod motion_ex;
 pub mod participants;
 pub mod lobby_info;
 pub mod session;
 pub mod session_history;
+pub mod tyre_sets;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Packet {
@@ -19,15 +24,15 @@ pub enum Packet {
     LapData(lap_data::LapDataPacket),
     Event(event::EventPacket),
     Participants(participants::ParticipantsPacket),
-    CarSetups(header::Header),
+    CarSetups(car_setups::CarSetupsPacket),
     CarTelemetry(car_telemetry::CarTelemetryPacket),
     CarStatus(car_status::CarStatusPacket),
     FinalClassification(final_classification::FinalClassificationPacket),
     LobbyInfo(lobby_info::LobbyInfoPacket),
-    CarDamage(header::Header),
+    CarDamage(car_damage::CarDamagePacket),
     SessionHistory(session_history::SessionHistoryPacket),
-    TyreSets(header::Header),
-    MotionEx(header::Header)
+    TyreSets(tyre_sets::TyreSetsPacket),
+    MotionEx(motion_ex::MotionExPacket)
 }
 
 impl Packet {
@@ -38,15 +43,55 @@ impl Packet {
             Packet::LapData(p) => p.header,
             Packet::Event(p) => p.header,
             Packet::Participants(p) => p.header,
-            Packet::CarSetups(p) => *p,
+            Packet::CarSetups(p) => p.header,
             Packet::CarTelemetry(p) => p.header,
             Packet::CarStatus(p) => p.header,
             Packet::FinalClassification(p) => p.header,
             Packet::LobbyInfo(p) => p.header,
-            Packet::CarDamage(p) => *p,
+            Packet::CarDamage(p) => p.header,
             Packet::SessionHistory(p) => p.header,
-            Packet::TyreSets(p) => *p,
-            Packet::MotionEx(p) => *p
+            Packet::TyreSets(p) => p.header,
+            Packet::MotionEx(p) => p.header
         }
     }
+
+    /// The datagram the packet was decoded from
+    pub fn raw(&self) -> Option<&Bytes> {
+        match self {
+            Packet::Motion(p) => p.raw.as_ref(),
+            Packet::Session(p) => p.raw.as_ref(),
+            Packet::LapData(p) => p.raw.as_ref(),
+            Packet::Event(p) => p.raw.as_ref(),
+            Packet::Participants(p) => p.raw.as_ref(),
+            Packet::CarSetups(p) => p.raw.as_ref(),
+            Packet::CarTelemetry(p) => p.raw.as_ref(),
+            Packet::CarStatus(p) => p.raw.as_ref(),
+            Packet::FinalClassification(p) => p.raw.as_ref(),
+            Packet::LobbyInfo(p) => p.raw.as_ref(),
+            Packet::CarDamage(p) => p.raw.as_ref(),
+            Packet::SessionHistory(p) => p.raw.as_ref(),
+            Packet::TyreSets(p) => p.raw.as_ref(),
+            Packet::MotionEx(p) => p.raw.as_ref()
+        }
+    }
+
+    pub(crate) fn set_raw(&mut self, raw: Bytes) {
+        let field = match self {
+            Packet::Motion(p) => &mut p.raw,
+            Packet::Session(p) => &mut p.raw,
+            Packet::LapData(p) => &mut p.raw,
+            Packet::Event(p) => &mut p.raw,
+            Packet::Participants(p) => &mut p.raw,
+            Packet::CarSetups(p) => &mut p.raw,
+            Packet::CarTelemetry(p) => &mut p.raw,
+            Packet::CarStatus(p) => &mut p.raw,
+            Packet::FinalClassification(p) => &mut p.raw,
+            Packet::LobbyInfo(p) => &mut p.raw,
+            Packet::CarDamage(p) => &mut p.raw,
+            Packet::SessionHistory(p) => &mut p.raw,
+            Packet::TyreSets(p) => &mut p.raw,
+            Packet::MotionEx(p) => &mut p.raw
+        };
+        *field = Some(raw);
+    }
 }
@@ -1,3 +1,4 @@
+use bytes::Bytes;
 use serde::{Serialize, Deserialize};
 
 use super::header::Header;
@@ -29,4 +30,8 @@ pub struct MotionPacket {
     pub header: Header,
 
     pub car_motion_data: Vec<CarMotionData>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
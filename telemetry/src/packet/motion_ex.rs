@@ -0,0 +1,51 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::header::Header;
+
+/// Extended motion data for the player car, wheel arrays are in the order RL, RR, FL, FR
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MotionExPacket {
+    pub header: Header,
+
+    pub suspension_position: [f32; 4],
+    pub suspension_velocity: [f32; 4],
+    pub suspension_acceleration: [f32; 4],
+    /// Speed of each wheel
+    pub wheel_speed: [f32; 4],
+    pub wheel_slip_ratio: [f32; 4],
+    pub wheel_slip_angle: [f32; 4],
+    pub wheel_lat_force: [f32; 4],
+    pub wheel_long_force: [f32; 4],
+    /// Height of centre of gravity above ground
+    pub height_of_cog_above_ground: f32,
+    /// Velocity in local space in m/s
+    pub local_velocity_x: f32,
+    pub local_velocity_y: f32,
+    pub local_velocity_z: f32,
+    /// Angular velocity in radians/s
+    pub angular_velocity_x: f32,
+    pub angular_velocity_y: f32,
+    pub angular_velocity_z: f32,
+    /// Angular acceleration in radians/s/s
+    pub angular_acceleration_x: f32,
+    pub angular_acceleration_y: f32,
+    pub angular_acceleration_z: f32,
+    /// Current front wheels angle in radians
+    pub front_wheels_angle: f32,
+    pub wheel_vert_force: [f32; 4],
+    /// Only sent by F1 24 and later
+    pub front_aero_height: Option<f32>,
+    /// Only sent by F1 24 and later
+    pub rear_aero_height: Option<f32>,
+    /// Only sent by F1 24 and later
+    pub front_roll_angle: Option<f32>,
+    /// Only sent by F1 24 and later
+    pub rear_roll_angle: Option<f32>,
+    /// Only sent by F1 24 and later
+    pub chassis_yaw: Option<f32>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
+}
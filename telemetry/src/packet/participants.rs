@@ -1,3 +1,4 @@
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use super::header::Header;
@@ -16,6 +17,8 @@ pub enum Team {
     Haas,
     McLaren,
     AlfaRomeo,
+    RB,
+    Sauber,
     RacingPoint,
     Renault,
     Konnersport,
@@ -33,9 +36,11 @@ pub enum Team {
     Charouz,
     BWT,
     Hitech,
+    PHM,
     VanAmersfoortRacing,
     Supercar,
     SafetyCar,
+    F1Generic,
     CustomTeam,
 }
 
@@ -63,7 +68,10 @@ pub struct ParticipantData {
     pub nationality: Option<celes::Country>,
     pub name: String,
     pub your_telemetry: Telemetry,
-    pub show_online_names: bool,
+    /// Not sent by F1 22
+    pub show_online_names: Option<bool>,
+    /// F1 World tech level, only sent by F1 24 and later
+    pub tech_level: Option<u16>,
     pub platform: Option<Platform>,
 }
 
@@ -72,4 +80,8 @@ pub struct ParticipantsPacket {
     pub header: Header,
     pub num_active_cars: u8,
     pub participants: Vec<ParticipantData>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
@@ -1,3 +1,4 @@
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use super::header::Header;
@@ -36,6 +37,16 @@ pub enum SessionType {
     ShortQ,
     #[serde(rename = "One Shot Qualifying")]
     OSQ,
+    #[serde(rename = "Sprint Shootout SQ1")]
+    SprintShootout1,
+    #[serde(rename = "Sprint Shootout SQ2")]
+    SprintShootout2,
+    #[serde(rename = "Sprint Shootout SQ3")]
+    SprintShootout3,
+    #[serde(rename = "Short Sprint Shootout")]
+    ShortSprintShootout,
+    #[serde(rename = "One Shot Sprint Shootout")]
+    OneShotSprintShootout,
     #[serde(rename = "Race")]
     R,
     #[serde(rename = "Race 2")]
@@ -98,6 +109,10 @@ pub enum Formula {
     Esports,
     #[serde(rename = "F2 2021")]
     F2_2021,
+    #[serde(rename = "F1 World")]
+    F1World,
+    #[serde(rename = "F1 Elimination")]
+    F1Elimination,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -213,6 +228,16 @@ pub enum GameMode {
     Career23,
     #[serde(rename = "Career Online (23)")]
     Career23Online,
+    #[serde(rename = "My Team Career (24)")]
+    MyTeamCareer24,
+    #[serde(rename = "Driver Career (24)")]
+    DriverCareer24,
+    #[serde(rename = "Career Online (24)")]
+    Career24Online,
+    #[serde(rename = "Challenge Career (24)")]
+    ChallengeCareer24,
+    #[serde(rename = "Story Mode (APXGP)")]
+    StoryModeAPXGP,
     Benchmark,
 }
 
@@ -228,6 +253,7 @@ pub enum Ruleset {
     Drift,
     AverageSpeedZone,
     RivalDuel,
+    Elimination,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -253,6 +279,98 @@ pub enum TempUnit {
     Fahrenheit,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum RecoveryMode {
+    None,
+    Flashbacks,
+    #[serde(rename = "Auto Recovery")]
+    AutoRecovery,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum FlashbackLimit {
+    Low,
+    Medium,
+    High,
+    Unlimited,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Hard,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum CarDamage {
+    Off,
+    Reduced,
+    Standard,
+    Simulation,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum CarDamageRate {
+    Reduced,
+    Standard,
+    Simulation,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Collisions {
+    Off,
+    #[serde(rename = "Player-to-Player Off")]
+    PlayerToPlayerOff,
+    On,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Experience {
+    Automatic,
+    Broadcast,
+    Immersive,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum Frequency {
+    Off,
+    Reduced,
+    Standard,
+    Increased,
+}
+
+/// Lobby rules, only sent by F1 24 and later
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct SessionRules {
+    pub equal_car_performance: bool,
+    pub recovery_mode: RecoveryMode,
+    pub flashback_limit: FlashbackLimit,
+    /// Simplified (false) or realistic (true) surface grip
+    pub realistic_surface_type: bool,
+    pub low_fuel_mode: Difficulty,
+    /// Manual (false) or assisted (true) race starts
+    pub assisted_race_starts: bool,
+    /// Surface only (false) or surface and carcass (true) tyre temperatures
+    pub carcass_tyre_temperature: bool,
+    pub pit_lane_tyre_sim: bool,
+    pub car_damage: CarDamage,
+    pub car_damage_rate: CarDamageRate,
+    pub collisions: Collisions,
+    pub collisions_off_for_first_lap_only: bool,
+    pub mp_unsafe_pit_release: bool,
+    pub mp_off_for_griefing: bool,
+    pub strict_corner_cutting: bool,
+    pub parc_ferme_rules: bool,
+    pub pit_stop_experience: Experience,
+    pub safety_car: Frequency,
+    pub safety_car_experience: Experience,
+    pub formation_lap: bool,
+    pub formation_lap_experience: Experience,
+    pub red_flags: Frequency,
+    pub affects_licence_level_solo: bool,
+    pub affects_licence_level_mp: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SessionPacket {
     pub header: Header,
@@ -296,11 +414,30 @@ pub struct SessionPacket {
     pub ruleset: Ruleset,
     pub time_of_day: u32,
     pub session_length: SessionLength,
-    pub speed_units_lead_player: SpeedUnit,
-    pub temperature_units_lead_player: TempUnit,
-    pub speed_units_secondary_player: SpeedUnit,
-    pub temperature_units_secondary_player: TempUnit,
-    pub num_safety_car_periods: u8,
-    pub num_virtual_safety_car_periods: u8,
-    pub num_red_flag_periods: u8,
+    /// Not sent by F1 22
+    pub speed_units_lead_player: Option<SpeedUnit>,
+    /// Not sent by F1 22
+    pub temperature_units_lead_player: Option<TempUnit>,
+    /// Not sent by F1 22
+    pub speed_units_secondary_player: Option<SpeedUnit>,
+    /// Not sent by F1 22
+    pub temperature_units_secondary_player: Option<TempUnit>,
+    /// Not sent by F1 22
+    pub num_safety_car_periods: Option<u8>,
+    /// Not sent by F1 22
+    pub num_virtual_safety_car_periods: Option<u8>,
+    /// Not sent by F1 22
+    pub num_red_flag_periods: Option<u8>,
+    /// Only sent by F1 24 and later
+    pub rules: Option<SessionRules>,
+    /// Sessions in the current weekend in chronological order, empty for games before F1 24
+    pub weekend_structure: Vec<SessionType>,
+    /// Distance in m around the track where sector 2 starts, only sent by F1 24 and later
+    pub sector_2_lap_distance_start: Option<f32>,
+    /// Distance in m around the track where sector 3 starts, only sent by F1 24 and later
+    pub sector_3_lap_distance_start: Option<f32>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 
 use super::{car_status::TyreCompound, header::Header};
@@ -36,4 +37,8 @@ pub struct SessionHistoryPacket {
 
     pub lap_history_data: Vec<LapHistoryData>,
     pub tyre_stint_history_data: Vec<TyreStintHistoryData>,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
 }
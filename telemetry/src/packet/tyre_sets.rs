@@ -0,0 +1,35 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use super::{car_status::TyreCompound, header::Header, session::SessionType};
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct TyreSetData {
+    pub actual_tyre_compound: TyreCompound,
+    pub visual_tyre_compound: TyreCompound,
+    /// Tyre wear (percentage)
+    pub wear: u8,
+    pub available: bool,
+    pub recommended_session: SessionType,
+    /// Laps left in this tyre set
+    pub life_span: u8,
+    /// Max number of laps recommended for this compound
+    pub usable_life: u8,
+    /// Lap delta time compared to the fitted set
+    pub lap_delta_time_in_ms: i16,
+    pub fitted: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TyreSetsPacket {
+    pub header: Header,
+
+    pub car_index: u8,
+    /// 13 dry sets followed by 7 wet sets
+    pub tyre_set_data: Vec<TyreSetData>,
+    pub fitted_index: u8,
+    /// The datagram the packet was decoded from, the encoder takes the bytes the model doesn't
+    /// keep from it. Set by [`crate::decode_packet`].
+    #[serde(skip)]
+    pub raw: Option<Bytes>,
+}
@@ -0,0 +1,805 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::packet::{
+    car_damage::CarDamageData,
+    car_setups::CarSetupData,
+    car_status::{CarStatusData, TyreCompound},
+    event::{Event, Penalty},
+    final_classification::FinalClassificationData,
+    lap_data::{LapData, PitStatus, Sector},
+    participants::ParticipantData,
+    session::{SafetyCarStatus, SessionPacket},
+    session_history::SessionHistoryPacket,
+    Packet,
+};
+
+/// A lap a car has completed, built up from the lap data packets sent while it was driven
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Lap {
+    pub lap_number: u8,
+    pub lap_time: Duration,
+    pub sector_1_time: Duration,
+    pub sector_2_time: Duration,
+    pub sector_3_time: Duration,
+    pub lap_valid: bool,
+    /// Position at the end of the lap
+    pub position: u8,
+    pub safety_car: bool,
+    pub virtual_safety_car: bool,
+    pub formation: bool,
+    pub in_lap: bool,
+    pub out_lap: bool,
+    pub infringements: Vec<Penalty>,
+}
+
+/// The lap a car is currently driving
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CurrentLap {
+    pub lap_number: u8,
+    pub sector_1_time: Duration,
+    pub sector_2_time: Duration,
+    pub lap_valid: bool,
+    pub safety_car: bool,
+    pub virtual_safety_car: bool,
+    pub formation: bool,
+    pub in_lap: bool,
+    pub out_lap: bool,
+    pub infringements: Vec<Penalty>,
+}
+
+/// Everything that is known about a single car, the latest packet of every kind wins
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CarState {
+    pub participant: Option<ParticipantData>,
+    /// Position, lap, sector, pit status, penalties and gaps as last sent by the game
+    pub lap_data: Option<LapData>,
+    pub status: Option<CarStatusData>,
+    pub damage: Option<CarDamageData>,
+    pub setup: Option<CarSetupData>,
+    pub session_history: Option<SessionHistoryPacket>,
+    /// Only set once the session has ended
+    pub classification: Option<FinalClassificationData>,
+    pub current_lap: Option<CurrentLap>,
+    pub laps: Vec<Lap>,
+    /// Every penalty issued to this car during the session
+    pub penalties: Vec<Penalty>,
+    pitting: bool,
+}
+
+impl CarState {
+    pub fn position(&self) -> Option<u8> {
+        self.lap_data.map(|ld| ld.car_position)
+    }
+
+    pub fn sector(&self) -> Option<Sector> {
+        self.lap_data.map(|ld| ld.sector)
+    }
+
+    pub fn pit_status(&self) -> Option<PitStatus> {
+        self.lap_data.map(|ld| ld.pit_status)
+    }
+
+    pub fn last_lap(&self) -> Option<&Lap> {
+        self.laps.last()
+    }
+
+    /// Fastest valid lap the car completed
+    pub fn best_lap(&self) -> Option<&Lap> {
+        self.laps
+            .iter()
+            .filter(|lap| lap.lap_valid)
+            .min_by_key(|lap| lap.lap_time)
+    }
+
+    pub fn tyre(&self) -> Option<TyreCompound> {
+        self.status.map(|s| s.visual_tyre_compound)
+    }
+
+    /// Not sent by F1 22
+    pub fn gap_to_car_in_front(&self) -> Option<Duration> {
+        self.lap_data.and_then(|ld| ld.delta_to_car_in_front)
+    }
+
+    /// Not sent by F1 22
+    pub fn gap_to_leader(&self) -> Option<Duration> {
+        self.lap_data.and_then(|ld| ld.delta_to_race_leader)
+    }
+
+    fn update_lap(&mut self, lap_data: LapData, safety_car_status: SafetyCarStatus) {
+        let current_lap = match self.current_lap.take() {
+            Some(current_lap) if current_lap.lap_number < lap_data.current_lap_num => {
+                // Just crossed the line, save the lap and start a new one
+                let sector_3_time = lap_data
+                    .last_lap_time
+                    .saturating_sub(current_lap.sector_1_time)
+                    .saturating_sub(current_lap.sector_2_time);
+                let out_lap = current_lap.in_lap;
+
+                self.laps.push(Lap {
+                    lap_number: current_lap.lap_number,
+                    lap_time: lap_data.last_lap_time,
+                    sector_1_time: current_lap.sector_1_time,
+                    sector_2_time: current_lap.sector_2_time,
+                    sector_3_time,
+                    lap_valid: current_lap.lap_valid,
+                    position: lap_data.car_position,
+                    safety_car: current_lap.safety_car,
+                    virtual_safety_car: current_lap.virtual_safety_car,
+                    formation: current_lap.formation,
+                    in_lap: current_lap.in_lap,
+                    out_lap: current_lap.out_lap,
+                    infringements: current_lap.infringements,
+                });
+
+                CurrentLap {
+                    out_lap,
+                    ..CurrentLap::start(&lap_data, safety_car_status)
+                }
+            }
+            Some(mut current_lap) if current_lap.lap_number == lap_data.current_lap_num => {
+                current_lap.safety_car |= safety_car_status == SafetyCarStatus::Full;
+                current_lap.virtual_safety_car |= safety_car_status == SafetyCarStatus::Virtual;
+                current_lap.formation |= safety_car_status == SafetyCarStatus::Formation;
+                current_lap.sector_1_time = lap_data.sector_1_time;
+                current_lap.sector_2_time = lap_data.sector_2_time;
+                current_lap.lap_valid = !lap_data.current_lap_invalid;
+
+                if !self.pitting && lap_data.pit_status == PitStatus::Pitting {
+                    current_lap.in_lap = true;
+                    self.pitting = true;
+                } else if self.pitting && lap_data.pit_status == PitStatus::None {
+                    self.pitting = false;
+                }
+
+                current_lap
+            }
+            // Lap numbers going back (e.g. after a flashback) are ignored
+            Some(current_lap) => current_lap,
+            None => CurrentLap::start(&lap_data, safety_car_status),
+        };
+
+        self.current_lap = Some(current_lap);
+        self.lap_data = Some(lap_data);
+    }
+}
+
+impl CurrentLap {
+    fn start(lap_data: &LapData, safety_car_status: SafetyCarStatus) -> Self {
+        Self {
+            lap_number: lap_data.current_lap_num,
+            sector_1_time: lap_data.sector_1_time,
+            sector_2_time: lap_data.sector_2_time,
+            lap_valid: !lap_data.current_lap_invalid,
+            safety_car: safety_car_status == SafetyCarStatus::Full,
+            virtual_safety_car: safety_car_status == SafetyCarStatus::Virtual,
+            formation: safety_car_status == SafetyCarStatus::Formation,
+            in_lap: false,
+            out_lap: false,
+            infringements: Vec::new(),
+        }
+    }
+}
+
+/// Up-to-date view of a session and all 22 cars in it, built by feeding it packets in the order
+/// they were received.
+///
+/// Packets from a different session than the one being tracked reset the state.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionState {
+    pub session_uid: Option<u64>,
+    /// Settings of the session as sent in the last session packet
+    pub session: Option<SessionPacket>,
+    pub cars: Vec<CarState>,
+    pub player_car_index: Option<u8>,
+}
+
+impl SessionState {
+    pub fn new() -> Self {
+        Self {
+            session_uid: None,
+            session: None,
+            cars: vec![CarState::default(); 22],
+            player_car_index: None,
+        }
+    }
+
+    pub fn car(&self, index: u8) -> Option<&CarState> {
+        self.cars.get(index as usize)
+    }
+
+    pub fn player_car(&self) -> Option<&CarState> {
+        self.player_car_index.and_then(|index| self.car(index))
+    }
+
+    /// Cars that are in the session, in race order
+    pub fn standings(&self) -> Vec<(u8, &CarState)> {
+        let mut standings: Vec<_> = self
+            .cars
+            .iter()
+            .enumerate()
+            .filter_map(|(i, car)| car.position().map(|_| (i as u8, car)))
+            .collect();
+        standings.sort_by_key(|(_, car)| car.position());
+        standings
+    }
+
+    pub fn safety_car_status(&self) -> SafetyCarStatus {
+        self.session
+            .as_ref()
+            .map(|s| s.safety_car_status)
+            .unwrap_or(SafetyCarStatus::No)
+    }
+
+    /// Packets sent outside of a session (session UID 0, like in the menus) are ignored, so they
+    /// don't throw away the state of the session they come between
+    pub fn update(&mut self, packet: &Packet) {
+        let header = packet.header();
+        if header.session_uid == 0 {
+            return;
+        }
+        if self.session_uid != Some(header.session_uid) {
+            *self = Self::new();
+            self.session_uid = Some(header.session_uid);
+        }
+        self.player_car_index = Some(header.player_car_index);
+
+        match packet {
+            Packet::Session(p) => {
+                self.session = Some(p.clone());
+            }
+            Packet::LapData(p) => {
+                let safety_car_status = self.safety_car_status();
+                for (car, lap_data) in self.cars.iter_mut().zip(&p.lap_data) {
+                    if let Some(lap_data) = lap_data {
+                        car.update_lap(*lap_data, safety_car_status);
+                    }
+                }
+            }
+            Packet::Event(p) => {
+                if let Event::PenaltyIssued(penalty) = p.event {
+                    if let Some(car) = self.cars.get_mut(penalty.vehicle_idx as usize) {
+                        if let Some(current_lap) = car.current_lap.as_mut() {
+                            current_lap.infringements.push(penalty);
+                        }
+                        car.penalties.push(penalty);
+                    }
+                }
+            }
+            Packet::Participants(p) => {
+                for (car, participant) in self.cars.iter_mut().zip(&p.participants) {
+                    car.participant = Some(participant.clone());
+                }
+            }
+            Packet::CarSetups(p) => {
+                for (car, setup) in self.cars.iter_mut().zip(&p.car_setups) {
+                    car.setup = Some(*setup);
+                }
+            }
+            Packet::CarStatus(p) => {
+                for (car, status) in self.cars.iter_mut().zip(&p.car_status_data) {
+                    car.status = Some(*status);
+                }
+            }
+            Packet::CarDamage(p) => {
+                for (car, damage) in self.cars.iter_mut().zip(&p.car_damage_data) {
+                    car.damage = Some(*damage);
+                }
+            }
+            Packet::FinalClassification(p) => {
+                for (car, classification) in self.cars.iter_mut().zip(&p.classification_data) {
+                    if let Some(classification) = classification {
+                        car.classification = Some(classification.clone());
+                    }
+                }
+            }
+            Packet::SessionHistory(p) => {
+                if let Some(car) = self.cars.get_mut(p.car_index as usize) {
+                    car.session_history = Some(p.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+
+    use super::*;
+    use crate::{
+        decode_packet,
+        packet::event::{InfringementType, PenaltyType},
+        test_util,
+    };
+
+    const SESSION_UID: u64 = 42;
+    const SESSION: u8 = 1;
+    const LAP_DATA: u8 = 2;
+    const EVENT: u8 = 3;
+    const PARTICIPANTS: u8 = 4;
+    const CAR_STATUS: u8 = 7;
+    const CAR_DAMAGE: u8 = 10;
+    const SESSION_HISTORY: u8 = 11;
+
+    /// An F1 23 packet of `size` bytes with the header filled in and the rest zeroed
+    /// An F1 23 packet of `size` bytes with the header filled in and the rest zeroed
+    fn packet(session_uid: u64, packet_id: u8, size: usize) -> BytesMut {
+        test_util::packet(2023, packet_id, session_uid, 0, 0, size)
+    }
+
+    fn decode(packet: BytesMut) -> Packet {
+        decode_packet(packet.freeze()).unwrap()
+    }
+
+    fn session(safety_car_status: u8) -> Packet {
+        let mut packet = packet(SESSION_UID, SESSION, 644);
+        packet[35] = 10; // Race
+        packet[36] = 7; // Silverstone
+        packet[153] = safety_car_status;
+        packet[614..618].copy_from_slice(&1234_u32.to_le_bytes());
+        decode(packet)
+    }
+
+    fn participants(names: &[&str]) -> Packet {
+        let mut packet = packet(SESSION_UID, PARTICIPANTS, 1306);
+        packet[29] = names.len() as u8;
+        for (i, name) in names.iter().enumerate() {
+            let entry = 30 + i * 58;
+            packet[entry + 1] = 255; // Not driven by the AI
+            packet[entry + 5] = 10 + i as u8;
+            packet[entry + 7..entry + 7 + name.len()].copy_from_slice(name.as_bytes());
+        }
+        decode(packet)
+    }
+
+    /// What the lap data packet says about a car, the cars not given are inactive
+    #[derive(Clone, Copy, Default)]
+    struct Car {
+        position: u8,
+        lap: u8,
+        sector: u8,
+        sector_1_ms: u16,
+        sector_2_ms: u16,
+        last_lap_ms: u32,
+        invalid: bool,
+        pit_status: u8,
+        penalties: u8,
+        gap_ms: u16,
+        gap_to_leader_ms: u16,
+    }
+
+    fn lap_data(cars: &[Car]) -> Packet {
+        let mut packet = packet(SESSION_UID, LAP_DATA, 1131);
+        for (i, car) in cars.iter().enumerate() {
+            let entry = &mut packet[29 + i * 50..29 + (i + 1) * 50];
+            entry[0..4].copy_from_slice(&car.last_lap_ms.to_le_bytes());
+            entry[8..10].copy_from_slice(&car.sector_1_ms.to_le_bytes());
+            entry[11..13].copy_from_slice(&car.sector_2_ms.to_le_bytes());
+            entry[14..16].copy_from_slice(&car.gap_ms.to_le_bytes());
+            entry[16..18].copy_from_slice(&car.gap_to_leader_ms.to_le_bytes());
+            entry[30] = car.position;
+            entry[31] = car.lap;
+            entry[32] = car.pit_status;
+            entry[34] = car.sector;
+            entry[35] = car.invalid.into();
+            entry[36] = car.penalties;
+            entry[43] = 2; // Active
+        }
+        decode(packet)
+    }
+
+    fn penalty(car: u8, seconds: u8) -> Packet {
+        let mut packet = packet(SESSION_UID, EVENT, 45);
+        packet[29..33].copy_from_slice(b"PENA");
+        packet[33] = 4; // Time penalty
+        packet[34] = 7; // Corner cutting gained time
+        packet[35] = car;
+        packet[36] = 255;
+        packet[37] = seconds;
+        decode(packet)
+    }
+
+    /// Only the car at index `soft` is on soft tyres
+    fn car_status(soft: u8) -> Packet {
+        let mut packet = packet(SESSION_UID, CAR_STATUS, 1239);
+        packet[29 + soft as usize * 55 + 26] = 16;
+        decode(packet)
+    }
+
+    fn car_damage(car: u8, front_left_wing: u8) -> Packet {
+        let mut packet = packet(SESSION_UID, CAR_DAMAGE, 953);
+        packet[29 + car as usize * 42 + 24] = front_left_wing;
+        decode(packet)
+    }
+
+    fn session_history(car: u8, lap_times_ms: &[u32]) -> Packet {
+        let mut packet = packet(SESSION_UID, SESSION_HISTORY, 1460);
+        packet[29] = car;
+        packet[30] = lap_times_ms.len() as u8;
+        for (i, lap_time) in lap_times_ms.iter().enumerate() {
+            let lap = 36 + i * 14;
+            packet[lap..lap + 4].copy_from_slice(&lap_time.to_le_bytes());
+            packet[lap + 13] = 0x0f; // Lap and sectors valid
+        }
+        decode(packet)
+    }
+
+    fn feed(state: &mut SessionState, packets: impl IntoIterator<Item = Packet>) {
+        for packet in packets {
+            state.update(&packet);
+        }
+    }
+
+    fn lap_times(car: &CarState) -> Vec<(u8, u64, u64, u64, u64)> {
+        car.laps
+            .iter()
+            .map(|lap| {
+                (
+                    lap.lap_number,
+                    lap.lap_time.as_millis() as u64,
+                    lap.sector_1_time.as_millis() as u64,
+                    lap.sector_2_time.as_millis() as u64,
+                    lap.sector_3_time.as_millis() as u64,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn positions_laps_and_sectors() {
+        let mut state = SessionState::new();
+        let leader = Car {
+            position: 1,
+            lap: 1,
+            ..Car::default()
+        };
+        let second = Car {
+            position: 2,
+            lap: 1,
+            gap_ms: 800,
+            gap_to_leader_ms: 800,
+            ..Car::default()
+        };
+        feed(
+            &mut state,
+            [
+                session(0),
+                participants(&["ALPHA", "BRAVO"]),
+                lap_data(&[leader, second]),
+                lap_data(&[
+                    Car {
+                        sector: 1,
+                        sector_1_ms: 30_000,
+                        ..leader
+                    },
+                    second,
+                ]),
+                lap_data(&[
+                    Car {
+                        sector: 2,
+                        sector_1_ms: 30_000,
+                        sector_2_ms: 31_000,
+                        ..leader
+                    },
+                    Car {
+                        sector: 1,
+                        sector_1_ms: 30_500,
+                        ..second
+                    },
+                ]),
+                // The second car overtakes before the line
+                lap_data(&[
+                    Car {
+                        position: 2,
+                        lap: 2,
+                        last_lap_ms: 91_000,
+                        gap_ms: 300,
+                        gap_to_leader_ms: 300,
+                        ..Car::default()
+                    },
+                    Car {
+                        position: 1,
+                        sector: 2,
+                        sector_1_ms: 30_500,
+                        sector_2_ms: 30_000,
+                        gap_ms: 0,
+                        gap_to_leader_ms: 0,
+                        ..second
+                    },
+                ]),
+            ],
+        );
+
+        let session = state.session.as_ref().unwrap();
+        assert_eq!(session.session_link_identifier, 1234);
+        assert_eq!(state.session_uid, Some(SESSION_UID));
+
+        let standings: Vec<_> = state
+            .standings()
+            .into_iter()
+            .map(|(i, car)| (i, car.participant.as_ref().unwrap().name.as_str()))
+            .collect();
+        assert_eq!(standings, [(1, "BRAVO"), (0, "ALPHA")]);
+
+        let alpha = state.car(0).unwrap();
+        assert_eq!(alpha.position(), Some(2));
+        assert_eq!(alpha.sector(), Some(Sector::Sector1));
+        assert_eq!(alpha.current_lap.as_ref().unwrap().lap_number, 2);
+        assert_eq!(lap_times(alpha), [(1, 91_000, 30_000, 31_000, 30_000)]);
+        assert_eq!(alpha.laps[0].position, 2);
+        assert_eq!(
+            alpha.gap_to_car_in_front(),
+            Some(Duration::from_millis(300))
+        );
+        assert_eq!(alpha.gap_to_leader(), Some(Duration::from_millis(300)));
+
+        let bravo = state.car(1).unwrap();
+        assert_eq!(bravo.position(), Some(1));
+        assert_eq!(bravo.sector(), Some(Sector::Sector3));
+        assert_eq!(bravo.current_lap.as_ref().unwrap().lap_number, 1);
+        assert_eq!(
+            bravo.current_lap.as_ref().unwrap().sector_2_time,
+            Duration::from_millis(30_000)
+        );
+        assert!(bravo.laps.is_empty());
+        assert_eq!(bravo.gap_to_car_in_front(), Some(Duration::ZERO));
+
+        // Inactive cars aren't in the standings but are still there
+        assert!(state.car(2).unwrap().position().is_none());
+        assert_eq!(state.cars.len(), 22);
+    }
+
+    #[test]
+    fn last_and_best_laps() {
+        let mut state = SessionState::new();
+        let lap = |lap, last_lap_ms, invalid| Car {
+            position: 1,
+            lap,
+            last_lap_ms,
+            invalid,
+            ..Car::default()
+        };
+        feed(
+            &mut state,
+            [
+                lap_data(&[lap(1, 0, false)]),
+                lap_data(&[lap(2, 92_000, false)]),
+                // Cut a corner, which makes this the fastest lap
+                lap_data(&[lap(2, 92_000, true)]),
+                lap_data(&[lap(3, 88_000, false)]),
+                lap_data(&[lap(4, 90_000, false)]),
+            ],
+        );
+
+        let car = state.car(0).unwrap();
+        let laps: Vec<_> = car
+            .laps
+            .iter()
+            .map(|lap| (lap.lap_number, lap.lap_time.as_millis(), lap.lap_valid))
+            .collect();
+        assert_eq!(
+            laps,
+            [(1, 92_000, true), (2, 88_000, false), (3, 90_000, true)]
+        );
+        assert_eq!(car.last_lap().unwrap().lap_number, 3);
+        assert_eq!(car.best_lap().unwrap().lap_number, 3);
+    }
+
+    #[test]
+    fn pit_stops() {
+        let mut state = SessionState::new();
+        let lap = |lap, pit_status| Car {
+            position: 1,
+            lap,
+            pit_status,
+            last_lap_ms: 90_000,
+            ..Car::default()
+        };
+        feed(
+            &mut state,
+            [
+                lap_data(&[lap(1, 0)]),
+                lap_data(&[lap(2, 0)]),
+                lap_data(&[lap(2, 1)]),
+                lap_data(&[lap(3, 2)]),
+                lap_data(&[lap(3, 0)]),
+                lap_data(&[lap(4, 0)]),
+            ],
+        );
+
+        let car = state.car(0).unwrap();
+        let laps: Vec<_> = car
+            .laps
+            .iter()
+            .map(|lap| (lap.lap_number, lap.in_lap, lap.out_lap))
+            .collect();
+        assert_eq!(
+            laps,
+            [(1, false, false), (2, true, false), (3, false, true)]
+        );
+        assert_eq!(car.pit_status(), Some(PitStatus::None));
+
+        state.update(&lap_data(&[lap(4, 1)]));
+        assert_eq!(state.car(0).unwrap().pit_status(), Some(PitStatus::Pitting));
+    }
+
+    #[test]
+    fn penalties() {
+        let mut state = SessionState::new();
+        let lap = |lap, penalties| Car {
+            position: 1,
+            lap,
+            penalties,
+            last_lap_ms: 90_000,
+            ..Car::default()
+        };
+        feed(
+            &mut state,
+            [
+                // Before the car has any lap data
+                penalty(0, 5),
+                lap_data(&[lap(1, 0)]),
+                lap_data(&[lap(2, 0)]),
+                penalty(0, 5),
+                lap_data(&[lap(2, 5)]),
+                lap_data(&[lap(3, 5)]),
+            ],
+        );
+
+        let car = state.car(0).unwrap();
+        assert_eq!(car.penalties.len(), 2);
+        assert!(car.penalties.iter().all(|penalty| {
+            matches!(penalty.penalty_type, PenaltyType::TimePenalty)
+                && matches!(
+                    penalty.infringement_type,
+                    InfringementType::CornerCuttingGainedTime
+                )
+                && penalty.time == 5
+        }));
+        let infringements: Vec<_> = car.laps.iter().map(|lap| lap.infringements.len()).collect();
+        assert_eq!(infringements, [0, 1]);
+        assert_eq!(car.lap_data.unwrap().penalties, 5);
+        assert!(state.car(1).unwrap().penalties.is_empty());
+    }
+
+    #[test]
+    fn safety_car_laps() {
+        let mut state = SessionState::new();
+        let lap = |lap| Car {
+            position: 1,
+            lap,
+            last_lap_ms: 90_000,
+            ..Car::default()
+        };
+        feed(
+            &mut state,
+            [
+                session(0),
+                lap_data(&[lap(1)]),
+                lap_data(&[lap(2)]),
+                session(2),
+                lap_data(&[lap(2)]),
+                session(1),
+                lap_data(&[lap(3)]),
+                session(0),
+                lap_data(&[lap(4)]),
+            ],
+        );
+
+        let laps: Vec<_> = state
+            .car(0)
+            .unwrap()
+            .laps
+            .iter()
+            .map(|lap| (lap.lap_number, lap.virtual_safety_car, lap.safety_car))
+            .collect();
+        assert_eq!(
+            laps,
+            [(1, false, false), (2, true, false), (3, false, true)]
+        );
+    }
+
+    #[test]
+    fn status_damage_and_history() {
+        let mut state = SessionState::new();
+        feed(
+            &mut state,
+            [
+                car_status(1),
+                car_damage(1, 40),
+                session_history(1, &[91_000, 90_500]),
+            ],
+        );
+
+        assert!(matches!(
+            state.car(1).unwrap().tyre(),
+            Some(TyreCompound::Soft)
+        ));
+        assert!(!matches!(
+            state.car(0).unwrap().tyre(),
+            Some(TyreCompound::Soft)
+        ));
+        assert_eq!(
+            state.car(1).unwrap().damage.unwrap().front_left_wing_damage,
+            40
+        );
+        assert_eq!(
+            state.car(0).unwrap().damage.unwrap().front_left_wing_damage,
+            0
+        );
+
+        let history = state.car(1).unwrap().session_history.as_ref().unwrap();
+        let lap_times: Vec<_> = history
+            .lap_history_data
+            .iter()
+            .map(|lap| lap.lap_time_in_ms)
+            .collect();
+        assert_eq!(lap_times, [91_000, 90_500]);
+        assert!(state.car(0).unwrap().session_history.is_none());
+    }
+
+    #[test]
+    fn flashbacks_dont_remove_laps() {
+        let mut state = SessionState::new();
+        let lap = |lap| Car {
+            position: 1,
+            lap,
+            last_lap_ms: 90_000,
+            ..Car::default()
+        };
+        feed(
+            &mut state,
+            [
+                lap_data(&[lap(1)]),
+                lap_data(&[lap(2)]),
+                lap_data(&[lap(1)]),
+                lap_data(&[lap(2)]),
+                lap_data(&[lap(3)]),
+            ],
+        );
+
+        let laps: Vec<_> = state
+            .car(0)
+            .unwrap()
+            .laps
+            .iter()
+            .map(|lap| lap.lap_number)
+            .collect();
+        assert_eq!(laps, [1, 2]);
+    }
+
+    #[test]
+    fn another_session_resets_the_state() {
+        let mut state = SessionState::new();
+        feed(&mut state, [session(0), participants(&["ALPHA"])]);
+
+        state.update(&decode(packet(SESSION_UID + 1, CAR_DAMAGE, 953)));
+
+        assert_eq!(state.session_uid, Some(SESSION_UID + 1));
+        assert!(state.session.is_none());
+        assert!(state.cars.iter().all(|car| car.participant.is_none()));
+        assert!(state.car(0).unwrap().damage.is_some());
+    }
+
+    #[test]
+    fn menu_packets_are_ignored() {
+        let mut state = SessionState::new();
+        feed(&mut state, [session(0), participants(&["ALPHA"])]);
+
+        state.update(&decode(packet(0, CAR_DAMAGE, 953)));
+
+        assert_eq!(state.session_uid, Some(SESSION_UID));
+        assert!(state.session.is_some());
+        assert!(state.car(0).unwrap().participant.is_some());
+        assert!(state.car(0).unwrap().damage.is_none());
+    }
+}
@@ -0,0 +1,31 @@
+//! Helpers for building packets in tests, also available to other crates with the `test-util`
+//! feature
+
+use bytes::{BufMut, BytesMut};
+
+/// A packet of `size` bytes with the header filled in and the rest zeroed. `frame` is used for
+/// both frame identifiers and, in hundredths of a second, for the session time.
+pub fn packet(
+    format: u16,
+    packet_id: u8,
+    session_uid: u64,
+    frame: u32,
+    player: u8,
+    size: usize,
+) -> BytesMut {
+    let mut packet = BytesMut::with_capacity(size);
+    packet.put_u16_le(format);
+    packet.put_u8((format % 100) as u8);
+    packet.put_u8(1);
+    packet.put_u8(0);
+    packet.put_u8(1);
+    packet.put_u8(packet_id);
+    packet.put_u64_le(session_uid);
+    packet.put_f32_le(frame as f32 / 100.0);
+    packet.put_u32_le(frame);
+    packet.put_u32_le(frame);
+    packet.put_u8(player);
+    packet.put_u8(255);
+    packet.resize(size, 0);
+    packet
+}